@@ -0,0 +1,473 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub const DEFAULT_PATH: &str = "./answers.toml";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => Err(format!("Invalid part {other}, expected 1 or 2")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hint::TooHigh => write!(f, "too high"),
+            Hint::TooLow => write!(f, "too low"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WrongGuess {
+    pub answer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<Hint>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct PartRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wrong: Vec<WrongGuess>,
+}
+
+/// Exclusive bounds on a numeric answer implied by the too-high / too-low guesses.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Bounds {
+    pub above: Option<i128>,
+    pub below: Option<i128>,
+}
+
+impl Bounds {
+    pub fn admits(&self, value: i128) -> bool {
+        self.above.is_none_or(|lo| value > lo) && self.below.is_none_or(|hi| value < hi)
+    }
+}
+
+impl Display for Bounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.above, self.below) {
+            (Some(lo), Some(hi)) => write!(f, "between {lo} and {hi}"),
+            (Some(lo), None) => write!(f, "above {lo}"),
+            (None, Some(hi)) => write!(f, "below {hi}"),
+            (None, None) => write!(f, "unbounded"),
+        }
+    }
+}
+
+impl PartRecord {
+    pub fn bounds(&self) -> Bounds {
+        let mut bounds = Bounds::default();
+        for guess in self.wrong.iter() {
+            let Ok(value) = guess.answer.parse::<i128>() else {
+                continue;
+            };
+            match guess.hint {
+                Some(Hint::TooLow) => bounds.above = bounds.above.max(Some(value)),
+                Some(Hint::TooHigh) => {
+                    bounds.below = Some(bounds.below.map_or(value, |hi| hi.min(value)))
+                }
+                None => {}
+            }
+        }
+        bounds
+    }
+
+    fn known_wrong(&self, answer: &str) -> Option<&WrongGuess> {
+        self.wrong.iter().find(|it| it.answer == answer)
+    }
+
+    fn out_of_bounds(&self, answer: &str) -> Option<Bounds> {
+        let bounds = self.bounds();
+        match answer.parse::<i128>() {
+            Ok(value) if !bounds.admits(value) => Some(bounds),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DayRecord {
+    #[serde(default, skip_serializing_if = "is_empty_record")]
+    pub part1: PartRecord,
+    #[serde(default, skip_serializing_if = "is_empty_record")]
+    pub part2: PartRecord,
+}
+
+fn is_empty_record(record: &PartRecord) -> bool {
+    *record == PartRecord::default()
+}
+
+impl DayRecord {
+    pub fn part(&self, part: Part) -> &PartRecord {
+        match part {
+            Part::One => &self.part1,
+            Part::Two => &self.part2,
+        }
+    }
+    pub fn part_mut(&mut self, part: Part) -> &mut PartRecord {
+        match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail(Mismatch),
+    New,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Mismatch {
+    Expected(String),
+    KnownWrong(Option<Hint>),
+    OutOfBounds(Bounds),
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::New => write!(f, "NEW"),
+            Verdict::Fail(Mismatch::Expected(expected)) => {
+                write!(f, "FAIL (expected {expected})")
+            }
+            Verdict::Fail(Mismatch::KnownWrong(Some(hint))) => {
+                write!(f, "FAIL (known wrong, {hint})")
+            }
+            Verdict::Fail(Mismatch::KnownWrong(None)) => write!(f, "FAIL (known wrong)"),
+            Verdict::Fail(Mismatch::OutOfBounds(bounds)) => {
+                write!(f, "FAIL (answer must be {bounds})")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Rejection {
+    AlreadyAccepted(String),
+    KnownWrong(Option<Hint>),
+    OutOfBounds(Bounds),
+    ContradictsAccepted,
+    /// The hint points away from the accepted answer.
+    HintContradictsAccepted {
+        hint: Hint,
+        accepted: String,
+    },
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::AlreadyAccepted(existing) => {
+                write!(f, "a different answer ({existing}) is already accepted")
+            }
+            Rejection::KnownWrong(Some(hint)) => {
+                write!(f, "answer is recorded as wrong ({hint})")
+            }
+            Rejection::KnownWrong(None) => write!(f, "answer is recorded as wrong"),
+            Rejection::OutOfBounds(bounds) => write!(f, "answer must be {bounds}"),
+            Rejection::ContradictsAccepted => write!(f, "answer is the accepted answer"),
+            Rejection::HintContradictsAccepted { hint, accepted } => {
+                write!(f, "answer can't be {hint} when {accepted} is accepted")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "could not access answers file: {e}"),
+            StoreError::Parse(e) => write!(f, "could not parse answers file: {e}"),
+            StoreError::Serialize(e) => write!(f, "could not write answers file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AnswerStore {
+    days: BTreeMap<u32, DayRecord>,
+}
+
+impl AnswerStore {
+    /// Loads the store from `path`, treating a missing file as an empty store.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(StoreError::Io(e)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, StoreError> {
+        toml::from_str(contents).map_err(StoreError::Parse)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StoreError> {
+        let contents = toml::to_string(self).map_err(StoreError::Serialize)?;
+        std::fs::write(path, contents).map_err(StoreError::Io)
+    }
+
+    pub fn record(&self, day: u32, part: Part) -> Option<&PartRecord> {
+        self.days.get(&day).map(|it| it.part(part))
+    }
+
+    fn record_mut(&mut self, day: u32, part: Part) -> &mut PartRecord {
+        self.days.entry(day).or_default().part_mut(part)
+    }
+
+    pub fn check(&self, day: u32, part: Part, answer: &str) -> Verdict {
+        let Some(record) = self.record(day, part) else {
+            return Verdict::New;
+        };
+        if let Some(accepted) = &record.accepted {
+            if accepted == answer {
+                Verdict::Pass
+            } else {
+                Verdict::Fail(Mismatch::Expected(accepted.clone()))
+            }
+        } else if let Some(guess) = record.known_wrong(answer) {
+            Verdict::Fail(Mismatch::KnownWrong(guess.hint))
+        } else if let Some(bounds) = record.out_of_bounds(answer) {
+            Verdict::Fail(Mismatch::OutOfBounds(bounds))
+        } else {
+            Verdict::New
+        }
+    }
+
+    pub fn accept(&mut self, day: u32, part: Part, answer: &str) -> Result<(), Rejection> {
+        let record = self.record_mut(day, part);
+        match &record.accepted {
+            Some(existing) if existing == answer => return Ok(()),
+            Some(existing) => return Err(Rejection::AlreadyAccepted(existing.clone())),
+            None => {}
+        }
+        if let Some(guess) = record.known_wrong(answer) {
+            return Err(Rejection::KnownWrong(guess.hint));
+        }
+        if let Some(bounds) = record.out_of_bounds(answer) {
+            return Err(Rejection::OutOfBounds(bounds));
+        }
+        record.accepted = Some(answer.to_string());
+        Ok(())
+    }
+
+    pub fn reject(
+        &mut self,
+        day: u32,
+        part: Part,
+        answer: &str,
+        hint: Option<Hint>,
+    ) -> Result<(), Rejection> {
+        let record = self.record_mut(day, part);
+        if record.accepted.as_deref() == Some(answer) {
+            return Err(Rejection::ContradictsAccepted);
+        }
+        let existing = record.wrong.iter().position(|it| it.answer == answer);
+        let hint = hint.or(existing.and_then(|idx| record.wrong[idx].hint));
+        if let (Some(hint), Some(accepted)) = (hint, &record.accepted) {
+            if let (Ok(value), Ok(expected)) = (answer.parse::<i128>(), accepted.parse::<i128>()) {
+                let consistent = match hint {
+                    Hint::TooHigh => value > expected,
+                    Hint::TooLow => value < expected,
+                };
+                if !consistent {
+                    return Err(Rejection::HintContradictsAccepted {
+                        hint,
+                        accepted: accepted.clone(),
+                    });
+                }
+            }
+        }
+        match existing {
+            Some(idx) => record.wrong[idx].hint = hint,
+            None => record.wrong.push(WrongGuess {
+                answer: answer.to_string(),
+                hint,
+            }),
+        }
+        Ok(())
+    }
+}
+
+// The file is keyed by `dayN` tables; these impls convert to and from numeric days so that the
+// file stays in day order rather than lexicographic order.
+impl Serialize for AnswerStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.days.iter().map(|(day, r)| (format!("day{day}"), r)))
+    }
+}
+
+impl<'de> Deserialize<'de> for AnswerStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw: BTreeMap<String, DayRecord> = BTreeMap::deserialize(deserializer)?;
+        let mut days = BTreeMap::new();
+        for (key, record) in raw {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|it| it.parse().ok())
+                .ok_or_else(|| D::Error::custom(format!("Invalid day key {key}")))?;
+            days.insert(day, record);
+        }
+        Ok(AnswerStore { days })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+[day1.part1]
+accepted = "11"
+
+[day2.part1]
+wrong = [
+    { answer = "100", hint = "too_high" },
+    { answer = "20", hint = "too_low" },
+    { answer = "50" },
+]
+"#;
+
+    #[test]
+    fn test_check() {
+        let store = AnswerStore::parse(EXAMPLE).unwrap();
+        assert_eq!(store.check(1, Part::One, "11"), Verdict::Pass);
+        assert_eq!(
+            store.check(1, Part::One, "12"),
+            Verdict::Fail(Mismatch::Expected("11".into()))
+        );
+        assert_eq!(store.check(1, Part::Two, "12"), Verdict::New);
+        assert_eq!(store.check(2, Part::One, "30"), Verdict::New);
+        assert_eq!(
+            store.check(2, Part::One, "50"),
+            Verdict::Fail(Mismatch::KnownWrong(None))
+        );
+        assert_eq!(
+            store.check(2, Part::One, "150"),
+            Verdict::Fail(Mismatch::OutOfBounds(Bounds {
+                above: Some(20),
+                below: Some(100)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_accept_respects_bounds() {
+        let mut store = AnswerStore::parse(EXAMPLE).unwrap();
+        assert!(matches!(
+            store.accept(2, Part::One, "100"),
+            Err(Rejection::KnownWrong(Some(Hint::TooHigh)))
+        ));
+        assert!(matches!(
+            store.accept(2, Part::One, "15"),
+            Err(Rejection::OutOfBounds(..))
+        ));
+        assert_eq!(
+            store.accept(1, Part::One, "12"),
+            Err(Rejection::AlreadyAccepted("11".into()))
+        );
+        assert_eq!(store.accept(2, Part::One, "42"), Ok(()));
+        assert_eq!(store.check(2, Part::One, "42"), Verdict::Pass);
+    }
+
+    #[test]
+    fn test_reject() {
+        let mut store = AnswerStore::default();
+        store.reject(3, Part::Two, "7", Some(Hint::TooLow)).unwrap();
+        assert_eq!(
+            store.check(3, Part::Two, "5"),
+            Verdict::Fail(Mismatch::OutOfBounds(Bounds {
+                above: Some(7),
+                below: None
+            }))
+        );
+        store.accept(3, Part::Two, "8").unwrap();
+        assert_eq!(
+            store.reject(3, Part::Two, "8", None),
+            Err(Rejection::ContradictsAccepted)
+        );
+        assert_eq!(
+            store.reject(3, Part::Two, "9", Some(Hint::TooLow)),
+            Err(Rejection::HintContradictsAccepted {
+                hint: Hint::TooLow,
+                accepted: "8".into()
+            })
+        );
+        assert_eq!(
+            store
+                .reject(3, Part::Two, "5", Some(Hint::TooHigh))
+                .map_err(|e| e.to_string()),
+            Err("answer can't be too high when 8 is accepted".into())
+        );
+        assert_eq!(store.reject(3, Part::Two, "9", Some(Hint::TooHigh)), Ok(()));
+        assert_eq!(store.record(3, Part::Two).unwrap().wrong.len(), 2);
+    }
+
+    #[test]
+    fn test_round_trip_keeps_day_order() {
+        let mut store = AnswerStore::default();
+        store.accept(10, Part::One, "1").unwrap();
+        store.accept(2, Part::Two, "2").unwrap();
+        let serialized = toml::to_string(&store).unwrap();
+        assert!(serialized.find("day2").unwrap() < serialized.find("day10").unwrap());
+        assert_eq!(AnswerStore::parse(&serialized).unwrap(), store);
+    }
+}
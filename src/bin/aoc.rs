@@ -0,0 +1,202 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use adventofcode2024::answers::{self, AnswerStore, Hint, Part, Verdict};
+use adventofcode2024::runner::{self, DayRun};
+use adventofcode2024::{registry, scaffold};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+const USAGE: &str = "\
+usage: aoc run [--jobs N] <day>... | all
+       aoc new <day>
+       aoc accept <day> <part> <answer>
+       aoc wrong <day> <part> <answer> [high|low]
+
+Day binaries are run from the same directory as this one, so build them first with
+`cargo build --release --bins`. Answers are recorded in answers.toml.";
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    s.parse().map_err(|_| format!("Invalid day {s}"))
+}
+
+fn parse_days(args: &[String]) -> Result<Vec<u32>, String> {
+    if args.is_empty() {
+        return Err("No days given".into());
+    }
+    if args.len() == 1 && args[0] == "all" {
+        return Ok(registry::DAYS.to_vec());
+    }
+    args.iter().map(|it| parse_day(it)).collect()
+}
+
+fn report(store: &AnswerStore, run: &DayRun) -> bool {
+    let mut ok = run.success;
+    for part in Part::BOTH {
+        match run.answer(part) {
+            Some(answer) => {
+                let verdict = store.check(run.day, part, answer);
+                ok &= !matches!(verdict, Verdict::Fail(..));
+                println!("day {:>2} part {part}: {answer:<24} {verdict}", run.day);
+            }
+            None if run.skipped(part) => {
+                println!("day {:>2} part {part}: {:<24} UNSOLVED", run.day, "-")
+            }
+            None => println!("day {:>2} part {part}: {:<24} MISSING", run.day, "-"),
+        }
+    }
+    if !run.success {
+        let error_line = run
+            .stderr
+            .lines()
+            .find(|it| it.contains("panicked") || it.contains(" failed: "));
+        println!(
+            "day {:>2} exited with an error{}",
+            run.day,
+            error_line.map(|it| format!(": {it}")).unwrap_or_default()
+        );
+    }
+    ok
+}
+
+/// Splits `--jobs N` out of the `run` arguments, defaulting to one job per core.
+fn parse_jobs(args: &[String]) -> Result<(usize, Vec<String>), String> {
+    let mut jobs = None;
+    let mut rest = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--jobs" || arg == "-j" {
+            let n = iter.next().ok_or("--jobs needs a value")?;
+            jobs = Some(
+                n.parse()
+                    .ok()
+                    .filter(|it| *it > 0)
+                    .ok_or_else(|| format!("Invalid job count {n}"))?,
+            );
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    let jobs = jobs.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|it| it.get())
+            .unwrap_or(1)
+    });
+    Ok((jobs, rest))
+}
+
+fn run_with_progress(day: u32, bar: &ProgressBar) -> std::io::Result<DayRun> {
+    bar.set_message("running");
+    bar.enable_steady_tick(Duration::from_millis(100));
+    let result = runner::run_day(day);
+    let status = match &result {
+        Ok(r) if r.success => format!("done in {:.2?}", r.elapsed),
+        Ok(r) => format!("failed after {:.2?}", r.elapsed),
+        Err(..) => "could not be run".to_string(),
+    };
+    bar.finish_with_message(status);
+    result
+}
+
+fn run(args: &[String]) -> Result<bool, String> {
+    let (jobs, args) = parse_jobs(args)?;
+    let days = parse_days(&args)?;
+    let store = AnswerStore::load(answers::DEFAULT_PATH).map_err(|e| e.to_string())?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let progress = MultiProgress::new();
+    let style = ProgressStyle::with_template("{spinner} day {prefix:>2}: {msg}").unwrap();
+    let bars: Vec<_> = days
+        .iter()
+        .map(|day| {
+            let bar = progress.add(ProgressBar::new_spinner().with_style(style.clone()));
+            bar.set_prefix(day.to_string());
+            bar.set_message("queued");
+            bar
+        })
+        .collect();
+    let jobs: Vec<_> = days.iter().copied().zip(bars.iter()).collect();
+    // `par_iter` keeps results in day order regardless of which finishes first.
+    let results: Vec<_> = pool.install(|| {
+        jobs.par_iter()
+            .map(|(day, bar)| (*day, run_with_progress(*day, bar)))
+            .collect()
+    });
+    progress.clear().map_err(|e| e.to_string())?;
+
+    let mut all_ok = true;
+    for (day, result) in results {
+        match result {
+            Ok(run) => all_ok &= report(&store, &run),
+            Err(e) => {
+                println!("day {day:>2} could not be run: {e}");
+                all_ok = false;
+            }
+        }
+    }
+    Ok(all_ok)
+}
+
+fn parse_hint(s: &str) -> Result<Hint, String> {
+    match s {
+        "high" => Ok(Hint::TooHigh),
+        "low" => Ok(Hint::TooLow),
+        other => Err(format!("Invalid hint {other}, expected high or low")),
+    }
+}
+
+fn record(args: &[String], accepted: bool) -> Result<bool, String> {
+    let (day, part, answer, hint) = match args {
+        [day, part, answer] => (day, part, answer, None),
+        [day, part, answer, hint] if !accepted => (day, part, answer, Some(parse_hint(hint)?)),
+        _ => return Err(USAGE.into()),
+    };
+    let day = parse_day(day)?;
+    let part: Part = part.parse()?;
+    let mut store = AnswerStore::load(answers::DEFAULT_PATH).map_err(|e| e.to_string())?;
+    let result = if accepted {
+        store.accept(day, part, answer)
+    } else {
+        store.reject(day, part, answer, hint)
+    };
+    result.map_err(|e| format!("Refusing to record day {day} part {part}: {e}"))?;
+    store
+        .save(answers::DEFAULT_PATH)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+fn new(args: &[String]) -> Result<bool, String> {
+    let [day] = args else {
+        return Err(USAGE.into());
+    };
+    let day = parse_day(day)?;
+    let written = scaffold::new_day(std::path::Path::new("."), day)
+        .map_err(|e| format!("Could not create day {day}: {e}"))?;
+    for path in written {
+        println!("wrote {}", path.display());
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(|it| it.as_str()) {
+        Some("run") => run(&args[1..]),
+        Some("new") => new(&args[1..]),
+        Some("accept") => record(&args[1..], true),
+        Some("wrong") => record(&args[1..], false),
+        _ => Err(USAGE.into()),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
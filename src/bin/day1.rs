@@ -1,57 +1,51 @@
-use std::collections::HashMap;
+use std::process::ExitCode;
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day1.txt").unwrap()
-}
-
-fn parse_inputs(inp: &str) -> (Vec<i32>, Vec<i32>) {
-    let mut first = vec![];
-    let mut second = vec![];
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+use adventofcode2024::stats::{sorted_total_distance, Counter};
+use adventofcode2024::table::parse_rows;
 
-    inp.lines()
-        .map(|l| l.split(" ").filter(|it| !it.is_empty()))
-        .for_each(|s| {
-            let s = s.collect::<Vec<_>>();
-            first.push(s[0].parse::<i32>().unwrap());
-            second.push(s[1].parse::<i32>().unwrap());
-        });
+struct Day1;
 
-    (first, second)
+fn parse_inputs(inp: &str) -> Result<(Vec<i32>, Vec<i32>), AocError> {
+    let pairs: Vec<(i32, i32)> = parse_rows(inp, "a location id")?;
+    Ok(pairs.into_iter().unzip())
 }
 
-fn part1(inp: &str) -> i32 {
-    let (mut first, mut second) = parse_inputs(inp);
-    first.sort();
-    second.sort();
+fn sim_score(first: &[i32], second: &[i32]) -> i64 {
+    let counts: Counter<_> = second.iter().collect();
     first
-        .into_iter()
-        .zip(second)
-        .map(|(a, b)| (a - b).abs())
+        .iter()
+        .map(|i| *i as i64 * counts.get(&i) as i64)
         .sum()
 }
 
-fn sim_score(first: &[i32], second: &[i32]) -> i32 {
-    let mut counts = HashMap::new();
-    second.iter().for_each(|i| {
-        counts
-            .entry(i)
-            .and_modify(|c| {
-                *c += 1;
-            })
-            .or_insert(1);
-    });
-    first
-        .iter()
-        .map(|i| *i * counts.get(i).copied().unwrap_or(0))
-        .sum()
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        let (first, second) = parse_inputs(inp)?;
+        Ok(sorted_total_distance(first, second).into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        let (first, second) = parse_inputs(inp)?;
+        Ok(sim_score(&first, &second).into())
+    }
 }
 
-fn part2(inp: &str) -> i32 {
-    let (first, second) = parse_inputs(inp);
-    sim_score(&first, &second)
+fn main() -> ExitCode {
+    solution::run::<Day1>()
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_bad_line() {
+        let err = Day1::part1("3   4\n4   3\n2   x5\n").unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.column, Some(5));
+    }
 }
@@ -1,156 +1,284 @@
+use std::process::ExitCode;
+
 use adventofcode2024::coords::{Bounded, Coord};
-use std::collections::HashSet;
-use std::rc::Rc;
+use adventofcode2024::dag::{count_paths, Paths};
+use adventofcode2024::error::{parse_as, AocError};
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day10;
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day10.txt").unwrap()
+/// What counts as a trail: it starts at height 0, goes up by exactly 1 at each step and ends at
+/// `peak`.
+#[derive(Clone, Copy, Debug)]
+struct TrailRules {
+    peak: u32,
+    diagonal: bool,
 }
 
-#[derive(Debug, Eq, Hash, PartialEq)]
-struct Trail {
-    prev: Option<Rc<Trail>>,
-    value: Coord,
+impl TrailRules {
+    const STANDARD: TrailRules = TrailRules {
+        peak: 9,
+        diagonal: false,
+    };
 }
 
-impl Trail {
-    fn extend(self: &Rc<Self>, step: Coord) -> Rc<Trail> {
-        Rc::new(Trail {
-            prev: Some(Rc::clone(self)),
-            value: step,
-        })
-    }
-    fn start(pos: Coord) -> Rc<Trail> {
-        Rc::new(Trail {
-            prev: None,
-            value: pos,
-        })
+/// The topographic map, with `None` for cells that can't be walked on. Rows are either digits, or
+/// heights separated by whitespace for maps that go above 9.
+struct Map {
+    heights: Vec<Vec<Option<u32>>>,
+}
+
+impl Bounded for Map {
+    fn in_bounds(&self, c: Coord) -> bool {
+        c.row >= 0
+            && c.col >= 0
+            && c.row < self.heights.len() as i64
+            && c.col < self.heights[0].len() as i64
     }
 }
 
-struct Map {
-    topography: Vec<Vec<u32>>,
-    peaks: Vec<Vec<HashSet<Coord>>>,
-    trails: Vec<Vec<HashSet<Rc<Trail>>>>,
+fn parse_height(cell: &str) -> Result<Option<u32>, AocError> {
+    match cell {
+        "." => Ok(None),
+        cell => parse_as(cell, "a height or .").map(Some),
+    }
 }
 
 impl Map {
-    fn new(inp: &str) -> Map {
-        let topography: Vec<Vec<_>> = inp
-            .lines()
-            .map(|row| row.chars().map(|it| it.to_digit(10).unwrap()).collect())
-            .collect();
-        let peaks = (0..topography.len())
-            .map(|_| vec![HashSet::new(); topography[0].len()])
-            .collect();
-        let trails = (0..topography.len())
-            .map(|_| vec![HashSet::new(); topography[0].len()])
-            .collect();
-        Map {
-            topography,
-            peaks,
-            trails,
+    fn new(inp: &str) -> Result<Map, AocError> {
+        let mut heights = vec![];
+        for (idx, line) in inp.lines().enumerate() {
+            let row = if line.contains(char::is_whitespace) {
+                line.split_whitespace().map(parse_height).collect()
+            } else {
+                line.char_indices()
+                    .map(|(col, c)| {
+                        parse_height(&line[col..col + c.len_utf8()])
+                            .map_err(|e| e.at_column(col + 1))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            };
+            let row = row.map_err(|e| e.at_line(idx + 1))?;
+            if heights
+                .first()
+                .is_some_and(|it: &Vec<_>| it.len() != row.len())
+            {
+                return Err(AocError::invalid("rows have different lengths").at_line(idx + 1));
+            }
+            heights.push(row);
         }
+        if heights.is_empty() {
+            return Err(AocError::invalid("the map is empty"));
+        }
+        Ok(Map { heights })
     }
-    fn get_top(&self, c: Coord) -> u32 {
-        self.topography[c.ri()][c.ci()]
-    }
-    fn get_peaks(&self, c: Coord) -> &HashSet<Coord> {
-        &self.peaks[c.ri()][c.ci()]
+
+    fn get(&self, c: Coord) -> Option<u32> {
+        self.heights[c.ri()][c.ci()]
     }
-    fn add_peak(&mut self, c: Coord, peak: Coord) {
-        self.peaks[c.ri()][c.ci()].insert(peak);
+
+    fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.heights.len()).flat_map(move |row| {
+            (0..self.heights[row].len()).map(move |col| Coord::from_xy(col, row))
+        })
     }
-    fn add_to_trail(&mut self, c: Coord, parent: &Rc<Trail>) {
-        self.trails[c.ri()][c.ci()].insert(parent.extend(c));
+
+    /// The cells one step further up a trail from `c`.
+    fn uphill(&self, c: Coord, rules: TrailRules) -> impl Iterator<Item = Coord> + '_ {
+        let next = self.get(c).map(|h| h + 1);
+        let neighbors = if rules.diagonal {
+            c.neighbors::<8>(self)
+        } else {
+            c.neighbors::<4>(self)
+        };
+        neighbors
+            .into_iter()
+            .filter(move |n| next.is_some() && self.get(*n) == next)
     }
-    fn start_trail(&mut self, c: Coord) {
-        self.trails[c.ri()][c.ci()].insert(Trail::start(c));
+
+    /// Counts trails with a DP over the graph of uphill steps: a cell's rating is the sum of the
+    /// ratings of the cells uphill from it, and the peaks it can reach are the union of theirs.
+    fn climb(&self, rules: TrailRules) -> Result<Climb<'_>, AocError> {
+        let at_height = |h: u32| self.coords().filter(move |c| self.get(*c) == Some(h));
+        let uphill = |from: Coord, to: Coord| {
+            self.get(from)
+                .is_some_and(|h| h < rules.peak && self.get(to) == Some(h + 1))
+        };
+        let paths = if rules.diagonal {
+            count_paths::<8, _, _>(self, uphill, at_height(0), at_height(rules.peak))?
+        } else {
+            count_paths::<4, _, _>(self, uphill, at_height(0), at_height(rules.peak))?
+        };
+        Ok(Climb {
+            map: self,
+            rules,
+            paths,
+        })
     }
-    fn get_trails(&self, c: Coord) -> &HashSet<Rc<Trail>> {
-        &self.trails[c.ri()][c.ci()]
+}
+
+/// The number of trails from each cell (its rating) and of peaks it can reach (its score).
+struct Climb<'a> {
+    map: &'a Map,
+    rules: TrailRules,
+    paths: Paths<u64>,
+}
+
+impl Climb<'_> {
+    fn trailheads(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.map
+            .coords()
+            .filter(|c| self.map.get(*c) == Some(0) && self.paths.count(*c) > 0)
     }
-    fn get_trail_score(&self, c: Coord) -> usize {
-        self.get_trails(c).len()
+
+    fn total_score(&self) -> usize {
+        self.trailheads()
+            .map(|c| self.paths.num_reachable_sinks(c))
+            .sum()
     }
-    fn get_score(&self, c: Coord) -> usize {
-        self.get_peaks(c).len()
+
+    fn total_rating(&self) -> Result<u64, AocError> {
+        self.trailheads()
+            .try_fold(0u64, |acc, c| acc.checked_add(self.paths.count(c)))
+            .ok_or_else(|| AocError::invalid("too many trails to count in a u64"))
     }
-    fn iter_numbers(&self, num: u32) -> Vec<Coord> {
-        let mut result = Vec::new();
-        for (row, row_vec) in self.topography.iter().enumerate() {
-            for (col, value) in row_vec.iter().enumerate() {
-                if *value == num {
-                    result.push(Coord {
-                        row: row as i64,
-                        col: col as i64,
-                    })
+
+    /// Every trail starting at `start`, built one at a time. Only cells with a rating are
+    /// followed, so every branch explored ends at a peak.
+    fn trails(&self, start: Coord) -> impl Iterator<Item = Vec<Coord>> + '_ {
+        let mut stack: Vec<Vec<Coord>> = if self.paths.count(start) > 0 {
+            vec![vec![start]]
+        } else {
+            vec![]
+        };
+        std::iter::from_fn(move || {
+            while let Some(path) = stack.pop() {
+                let last = *path.last().unwrap();
+                if self.map.get(last) == Some(self.rules.peak) {
+                    return Some(path);
+                }
+                for next in self.map.uphill(last, self.rules) {
+                    if self.paths.count(next) > 0 {
+                        let mut longer = path.clone();
+                        longer.push(next);
+                        stack.push(longer);
+                    }
                 }
             }
-        }
-        result
+            None
+        })
     }
-    fn compute_total_map_score(&self) -> usize {
-        self.iter_numbers(0)
-            .into_iter()
-            .map(|c| self.get_score(c))
-            .sum()
+}
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(Map::new(inp)?
+            .climb(TrailRules::STANDARD)?
+            .total_score()
+            .into())
     }
-    fn compute_total_trail_score(&self) -> usize {
-        self.iter_numbers(0)
-            .into_iter()
-            .map(|c| self.get_trail_score(c))
-            .sum()
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Map::new(inp)?
+            .climb(TrailRules::STANDARD)?
+            .total_rating()
+            .map(Answer::from)
     }
 }
 
-impl Bounded for Map {
-    fn in_bounds(&self, c: Coord) -> bool {
-        c.row < self.topography.len() as i64
-            && c.col < self.topography[0].len() as i64
-            && c.row >= 0
-            && c.col >= 0
+/// Prints every trail from each trailhead, as the coordinates along it.
+fn print_trails(inp: &str) -> Result<(), AocError> {
+    let map = Map::new(inp)?;
+    let climb = map.climb(TrailRules::STANDARD)?;
+    let mut trailheads: Vec<_> = climb.trailheads().collect();
+    trailheads.sort_by_key(|c| (c.row, c.col));
+    for start in trailheads {
+        for trail in climb.trails(start) {
+            let steps: Vec<_> = trail
+                .iter()
+                .map(|c| format!("({},{})", c.row, c.col))
+                .collect();
+            println!("{}", steps.join(" -> "));
+        }
     }
+    Ok(())
 }
 
-fn part1(inp: &str) -> usize {
-    let mut map = Map::new(inp);
-    for pos in map.iter_numbers(9) {
-        map.add_peak(pos, pos);
-    }
-    for height in (0..9).rev() {
-        for pos in map.iter_numbers(height) {
-            for neighbor in pos.neighbors::<4>(&map) {
-                if map.get_top(neighbor) == height + 1 {
-                    for peak in map.get_peaks(neighbor).clone() {
-                        map.add_peak(pos, peak);
-                    }
-                }
+fn main() -> ExitCode {
+    if std::env::args().any(|it| it == "--trails") {
+        return match Day10::input().and_then(|inp| print_trails(&inp)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
             }
-        }
+        };
     }
-    map.compute_total_map_score()
+    solution::run::<Day10>()
 }
 
-fn part2(inp: &str) -> usize {
-    let mut map = Map::new(inp);
-    for pos in map.iter_numbers(9) {
-        map.start_trail(pos);
-    }
-    for height in (0..9).rev() {
-        for pos in map.iter_numbers(height) {
-            for neighbor in pos.neighbors::<4>(&map) {
-                if map.get_top(neighbor) == height + 1 {
-                    for trail in map.get_trails(neighbor).clone() {
-                        map.add_to_trail(pos, &trail);
-                    }
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day10::part1(EXAMPLE), Ok(Answer::Num(36)));
+        assert_eq!(Day10::part2(EXAMPLE), Ok(Answer::Num(81)));
+        let score_2 = "...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9";
+        assert_eq!(Day10::part1(score_2), Ok(Answer::Num(2)));
+        let rating_3 = ".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....";
+        assert_eq!(Day10::part2(rating_3), Ok(Answer::Num(3)));
+    }
+
+    #[test]
+    fn test_trails() {
+        let map = Map::new(EXAMPLE).unwrap();
+        let climb = map.climb(TrailRules::STANDARD).unwrap();
+        let mut total = 0;
+        for start in climb.trailheads() {
+            let trails: Vec<_> = climb.trails(start).collect();
+            assert_eq!(trails.len() as u64, climb.paths.count(start));
+            for trail in trails {
+                assert_eq!(trail.len(), 10);
+                assert!(trail.iter().zip(0..).all(|(c, h)| map.get(*c) == Some(h)));
             }
+            total += 1;
         }
+        assert_eq!(total, 9);
     }
-    map.compute_total_trail_score()
-}
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+    #[test]
+    fn test_rules() {
+        let map = Map::new("0 1 2 3 4 5\n. . . . . 6\n12 11 10 9 8 7").unwrap();
+        let rules = TrailRules {
+            peak: 12,
+            diagonal: false,
+        };
+        assert_eq!(map.climb(rules).unwrap().total_rating(), Ok(1));
+        let map = Map::new("0.\n.1").unwrap();
+        let rules = TrailRules {
+            peak: 1,
+            diagonal: false,
+        };
+        assert_eq!(map.climb(rules).unwrap().total_score(), 0);
+        let rules = TrailRules {
+            diagonal: true,
+            ..rules
+        };
+        assert_eq!(map.climb(rules).unwrap().total_score(), 1);
+        assert_eq!(Map::new("012\n34").err().and_then(|e| e.line), Some(2));
+    }
 }
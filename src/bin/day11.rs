@@ -1,15 +1,17 @@
-use std::collections::HashMap;
-use std::fmt::Display;
+use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
 
-const INPUTS: [u64; 8] = [872027, 227, 18, 9760, 0, 4, 67716, 9245696];
+use adventofcode2024::digits::Digits;
+use adventofcode2024::error::{parse_as, AocError};
+use adventofcode2024::multiset::MultisetEvolver;
+use adventofcode2024::solution::{self, Answer, Solution};
+use itertools::Itertools;
+use num_bigint::BigUint;
+use num_traits::{CheckedAdd, One, Zero};
 
-trait Digits: Display {
-    fn num_digits(&self) -> usize {
-        self.to_string().len()
-    }
-}
+struct Day11;
 
-impl Digits for u64 {}
+const INPUTS: [Stone; 8] = [872027, 227, 18, 9760, 0, 4, 67716, 9245696];
 
 enum OneOrTwo<T> {
     One(T),
@@ -51,58 +53,261 @@ impl<T: Clone> IntoIterator for OneOrTwo<T> {
     }
 }
 
-fn rule(num: u64) -> OneOrTwo<u64> {
+/// Stones are read as `u64`s but held as `u128`s, since a stone with an odd number of digits gets
+/// 2024 times bigger. That can only happen twice in a row before the digits even out and it
+/// splits, so nothing a `u64` starts from gets past 26 digits.
+type Stone = u128;
+
+fn rule(num: Stone) -> OneOrTwo<Stone> {
     use OneOrTwo::*;
     match num {
         0 => One(1),
-        n if n.num_digits() % 2 == 0 => {
-            let mut n_str = n.to_string();
-            let second = n_str.split_off(n_str.len() / 2);
-            Two(n_str.parse().unwrap(), second.parse().unwrap())
+        n => match n.split_half() {
+            Some((first, second)) => Two(first, second),
+            None => One(n * 2024),
+        },
+    }
+}
+
+fn parse_inputs(inp: &str) -> Result<Vec<Stone>, AocError> {
+    inp.split_whitespace()
+        .map(|it| parse_as::<u64>(it, "a stone").map(Stone::from))
+        .collect()
+}
+
+fn too_many() -> AocError {
+    AocError::invalid("too many stones to count in a u128")
+}
+
+/// Counts how many stones a stone becomes after some number of blinks, as an `N`: `u128` is
+/// plenty for the puzzle, and a `BigUint` never runs out. The count for every (value, blinks)
+/// pair worked out along the way is kept, so later questions about any stone can reuse them.
+struct StoneCounter<N = u128> {
+    cache: HashMap<(Stone, usize), N>,
+}
+
+impl<N> Default for StoneCounter<N> {
+    fn default() -> Self {
+        StoneCounter {
+            cache: HashMap::new(),
         }
-        n => One(n * 2024),
     }
 }
 
-fn part1(inp: &[u64]) -> usize {
-    let mut stones = inp.to_vec();
-    for _ in 0..25 {
-        stones = stones.into_iter().flat_map(rule).collect();
+impl<N: Clone + Zero + One + CheckedAdd> StoneCounter<N> {
+    fn count(&mut self, value: Stone, blinks: usize) -> Result<N, AocError> {
+        if blinks == 0 {
+            return Ok(N::one());
+        }
+        if let Some(count) = self.cache.get(&(value, blinks)) {
+            return Ok(count.clone());
+        }
+        let mut total = N::zero();
+        for child in rule(value) {
+            total = total
+                .checked_add(&self.count(child, blinks - 1)?)
+                .ok_or_else(too_many)?;
+        }
+        self.cache.insert((value, blinks), total.clone());
+        Ok(total)
     }
 
-    stones.len()
+    fn total(&mut self, stones: &[Stone], blinks: usize) -> Result<N, AocError> {
+        stones.iter().try_fold(N::zero(), |acc, stone| {
+            acc.checked_add(&self.count(*stone, blinks)?)
+                .ok_or_else(too_many)
+        })
+    }
 }
 
-fn solve_by_counts(inp: &[u64], n_iters: usize) -> usize {
-    let mut counts: HashMap<u64, usize> = HashMap::new();
-    for item in inp {
-        counts.entry(*item).and_modify(|v| *v += 1).or_insert(1);
+/// The values that ever appear starting from `stones`, grouped by the first blink they appear
+/// after. The rule only ever produces finitely many values from small stones, so this ends; the
+/// last group is the last blink that shows anything new.
+fn reachable(stones: &[Stone]) -> Vec<HashSet<Stone>> {
+    let mut seen: HashSet<Stone> = stones.iter().copied().collect();
+    let mut groups = vec![seen.clone()];
+    loop {
+        let next: HashSet<Stone> = groups
+            .last()
+            .unwrap()
+            .iter()
+            .flat_map(|it| rule(*it))
+            .filter(|it| !seen.contains(it))
+            .collect();
+        if next.is_empty() {
+            return groups;
+        }
+        seen.extend(next.iter().copied());
+        groups.push(next);
     }
-    for _ in 0..n_iters {
-        let mut next_counts = HashMap::new();
+}
 
-        for (k, v) in counts {
-            let next = rule(k);
-            for next_item in next {
-                next_counts
-                    .entry(next_item)
-                    .and_modify(|v_next| *v_next += v)
-                    .or_insert(v);
-            }
-        }
+fn blink(inp: &str, blinks: usize) -> Result<Answer, AocError> {
+    let total: u128 = StoneCounter::default().total(&parse_inputs(inp)?, blinks)?;
+    i128::try_from(total)
+        .map(Answer::Num)
+        .map_err(|_| too_many())
+}
 
-        counts = next_counts;
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    fn input() -> Result<String, AocError> {
+        Ok(INPUTS.iter().join(" "))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        blink(inp, 25)
     }
 
-    counts.values().sum()
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        blink(inp, 75)
+    }
 }
 
-fn part2(inp: &[u64]) -> usize {
-    solve_by_counts(inp, 75)
+/// Prints how many stones there are after each of `blinks` blinks, and how many distinct values
+/// they have between them.
+fn print_history(inp: &str, blinks: usize) -> Result<(), AocError> {
+    let mut stones = MultisetEvolver::new(parse_inputs(inp)?, rule)?;
+    for stats in stones.run(blinks)? {
+        println!(
+            "{:>3}: {} stones, {} distinct",
+            stats.generation, stats.size, stats.distinct
+        );
+    }
+    Ok(())
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&INPUTS));
-    println!("Part 1 (alt): {}", solve_by_counts(&INPUTS, 25));
-    println!("Part 2: {}", part2(&INPUTS));
+/// Prints the exact number of stones after `blinks` blinks, however many that is.
+fn print_count(inp: &str, blinks: &str) -> Result<(), AocError> {
+    let blinks = parse_as(blinks, "a number of blinks")?;
+    let total: BigUint = StoneCounter::default().total(&parse_inputs(inp)?, blinks)?;
+    println!("{total}");
+    Ok(())
+}
+
+/// Prints how many distinct values the stones ever take, and when the last new one shows up.
+fn print_reachable(inp: &str) -> Result<(), AocError> {
+    let groups = reachable(&parse_inputs(inp)?);
+    for (blinks, group) in groups.iter().enumerate() {
+        println!("{blinks:>3}: {} new values", group.len());
+    }
+    println!(
+        "{} distinct values, none new after blink {}",
+        groups.iter().map(|it| it.len()).sum::<usize>(),
+        groups.len() - 1
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    // `--blinks <n> [<stones>]` counts the stones after `n` blinks without overflowing, starting
+    // from the given stones or the puzzle input.
+    let mut args = std::env::args().skip_while(|it| it != "--blinks");
+    if args.next().is_some() {
+        let Some(blinks) = args.next() else {
+            eprintln!("usage: day11 --blinks <n> [<stones>]");
+            return ExitCode::FAILURE;
+        };
+        let stones: Vec<String> = args.collect();
+        let stones = if stones.is_empty() {
+            Day11::input()
+        } else {
+            Ok(stones.join(" "))
+        };
+        return match stones.and_then(|inp| print_count(&inp, &blinks)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    // `--history [<n>]` shows how the population grows over the first `n` blinks (75 by default).
+    let mut args = std::env::args().skip_while(|it| it != "--history");
+    if args.next().is_some() {
+        let blinks = args
+            .next()
+            .map_or(Ok(75), |it| parse_as(&it, "a number of blinks"));
+        return match blinks.and_then(|n| Day11::input().and_then(|inp| print_history(&inp, n))) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if std::env::args().any(|it| it == "--reachable") {
+        return match Day11::input().and_then(|inp| print_reachable(&inp)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    solution::run::<Day11>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve_by_counts(inp: &[Stone], n_iters: usize) -> Result<u128, AocError> {
+        let mut stones = MultisetEvolver::new(inp.iter().copied(), rule)?;
+        stones.run(n_iters)?;
+        Ok(stones.size())
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day11::part1("125 17"), Ok(Answer::Num(55312)));
+        let mut counter = StoneCounter::<u128>::default();
+        assert_eq!(counter.total(&[125, 17], 6), Ok(22));
+        for blinks in [0, 1, 10, 40] {
+            assert_eq!(
+                counter.total(&INPUTS, blinks),
+                solve_by_counts(&INPUTS, blinks)
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_stones() {
+        // Multiplying by 2024 overflows a u64 from here, and then again.
+        let big = 9_999_999_999_999_999_999;
+        let mut counter = StoneCounter::<u128>::default();
+        let counts: Vec<_> = (1..=7).map(|it| counter.count(big, it).unwrap()).collect();
+        assert_eq!(counts, vec![1, 1, 2, 2, 3, 6, 12]);
+        let stones = parse_inputs(&format!("{} {big}", u64::MAX)).unwrap();
+        assert_eq!(counter.total(&stones, 40), solve_by_counts(&stones, 40));
+        assert!(parse_inputs("18446744073709551616").is_err());
+    }
+
+    #[test]
+    fn test_reachable() {
+        let groups = reachable(&[0]);
+        assert_eq!(groups[0], HashSet::from([0]));
+        assert_eq!(groups[1], HashSet::from([1]));
+        assert_eq!(groups[2], HashSet::from([2024]));
+        assert_eq!(groups[3], HashSet::from([20, 24]));
+        // Once nothing new shows up, every value's children are already in the set.
+        let all: HashSet<Stone> = groups.iter().flatten().copied().collect();
+        assert!(all
+            .iter()
+            .flat_map(|it| rule(*it))
+            .all(|it| all.contains(&it)));
+        // The counts keep growing long after the values stop.
+        let mut counter = StoneCounter::<u128>::default();
+        assert!(counter.count(0, 200).unwrap() > 1 << 100);
+        assert!(counter.count(0, 1000).is_err());
+        let mut big = StoneCounter::<BigUint>::default();
+        assert_eq!(
+            big.count(0, 200).unwrap(),
+            counter.count(0, 200).unwrap().into()
+        );
+        let after_1000 = big.count(0, 1000).unwrap();
+        assert!(after_1000.bits() > 128);
+        assert_eq!(after_1000.to_string().len(), 182);
+    }
 }
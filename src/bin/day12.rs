@@ -1,11 +1,12 @@
 use std::collections::HashSet;
 use std::ops::Index;
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Bounded, Coord, InfinitePlane};
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day12.txt").unwrap()
-}
+struct Day12;
 
 struct Garden {
     plots: Vec<Vec<char>>,
@@ -29,10 +30,15 @@ impl Index<Coord> for Garden {
 }
 
 impl Garden {
-    fn new(inp: &str) -> Self {
-        Self {
-            plots: inp.lines().map(|it| it.chars().collect()).collect(),
+    fn new(inp: &str) -> Result<Self, AocError> {
+        let plots: Vec<Vec<char>> = inp.lines().map(|it| it.chars().collect()).collect();
+        if plots.is_empty() {
+            return Err(AocError::invalid("the garden is empty"));
+        }
+        if let Some(idx) = plots.iter().position(|it| it.len() != plots[0].len()) {
+            return Err(AocError::invalid("rows have different lengths").at_line(idx + 1));
         }
+        Ok(Self { plots })
     }
     fn get(&self, index: Coord) -> Option<char> {
         if self.in_bounds(index) {
@@ -151,24 +157,30 @@ impl Region {
     }
 }
 
-fn part1(inp: &str) -> usize {
-    let garden = Garden::new(inp);
-    let regions = garden.build_regions();
-    regions
-        .into_iter()
-        .map(|it| it.area() * it.perimeter(&garden))
-        .sum()
-}
-fn part2(inp: &str) -> usize {
-    let garden = Garden::new(inp);
-    let regions = garden.build_regions();
-    regions
-        .into_iter()
-        .map(|it| it.area() * it.sides_count(&garden))
-        .sum()
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        let garden = Garden::new(inp)?;
+        let regions = garden.build_regions();
+        Ok(regions
+            .into_iter()
+            .map(|it| it.area() * it.perimeter(&garden))
+            .sum::<usize>()
+            .into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        let garden = Garden::new(inp)?;
+        let regions = garden.build_regions();
+        Ok(regions
+            .into_iter()
+            .map(|it| it.area() * it.sides_count(&garden))
+            .sum::<usize>()
+            .into())
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+fn main() -> ExitCode {
+    solution::run::<Day12>()
 }
@@ -1,6 +1,14 @@
+use std::process::ExitCode;
+
 use adventofcode2024::coords::{Coord, CoordDiff};
+use adventofcode2024::error::AocError;
+use adventofcode2024::linalg::{solve_2x2, Rational, Solutions};
+use adventofcode2024::numtheory::mod_inverse;
 use adventofcode2024::parsers::{Parser, Parsers};
-use adventofcode2024::{Chunk, IntoChunkedIter};
+use adventofcode2024::solution::{self, Answer};
+use adventofcode2024::IntoChunkedIter;
+
+struct Day13;
 
 #[derive(Clone, Copy, Debug)]
 struct ClawGame {
@@ -18,31 +26,30 @@ struct Solution {
 
 impl ClawGame {
     fn solve(&self) -> Option<Solution> {
-        let num = self.a.y() * self.goal.x() - self.goal.y() * self.a.x();
-        let denom = self.b.x() * self.a.y() - self.a.x() * self.b.y();
-        if num % denom != 0 {
-            return None;
-        }
-        let num_b = num / denom;
-        if num_b < 0 {
-            return None;
-        }
-        let na_num = self.goal.x() - num_b * self.b.x();
-        if na_num % self.a.x() != 0 {
-            return None;
-        }
-        let num_a = na_num / self.a.x();
-        if num_a < 0 {
+        let presses = solve_2x2(
+            [[self.a.x(), self.b.x()], [self.a.y(), self.b.y()]],
+            [self.goal.x(), self.goal.y()],
+        );
+        let (num_a, num_b) = match presses {
+            Solutions::None => return None,
+            Solutions::Unique(x) => (x[0].to_integer()?, x[1].to_integer()?),
+            Solutions::Family {
+                particular,
+                directions,
+            } => cheapest(&particular, &directions)?,
+        };
+        if num_a < 0 || num_b < 0 {
             return None;
         }
         Some(Solution {
-            num_a,
-            num_b,
-            cost: 3 * num_a + num_b,
+            num_a: num_a.try_into().ok()?,
+            num_b: num_b.try_into().ok()?,
+            cost: cost(num_a, num_b).try_into().ok()?,
         })
     }
 
-    fn parse(inp: &Chunk<3, &str>) -> ClawGame {
+    /// Parses the three lines describing one machine; error lines are relative to the first.
+    fn parse(inp: [&str; 3]) -> Result<ClawGame, AocError> {
         let parse_xy = || {
             Parsers::lit("X+")
                 .then(Parsers::num())
@@ -60,34 +67,91 @@ impl ClawGame {
             .followed_by(", Y=")
             .and(Parsers::num())
             .map(|(x, y)| Coord::from_xy(x as i64, y as i64));
-        match inp {
-            Chunk::Partial(..) => panic!("Unexpectedly found partial chunk"),
-            Chunk::Complete(arr) => ClawGame {
-                a: a.apply(arr[0]).unwrap_value(),
-                b: b.apply(arr[1]).unwrap_value(),
-                goal: prize.apply(arr[2]).unwrap_value(),
-            },
-        }
+        Ok(ClawGame {
+            a: a.apply(inp[0])
+                .value_or_error("a Button A line", inp[0])
+                .map_err(|e| e.at_line(1))?,
+            b: b.apply(inp[1])
+                .value_or_error("a Button B line", inp[1])
+                .map_err(|e| e.at_line(2))?,
+            goal: prize
+                .apply(inp[2])
+                .value_or_error("a Prize line", inp[2])
+                .map_err(|e| e.at_line(3))?,
+        })
     }
 }
 
-fn parse_input(inp: &str) -> Vec<ClawGame> {
+fn cost(num_a: i128, num_b: i128) -> i128 {
+    3 * num_a + num_b
+}
+
+/// The cheapest presses when both buttons move the claw along the same line as the prize, so
+/// there can be many ways to reach it. If only one button is free, the presses are
+/// `particular + t * direction` where `t` is how many times that button is pressed. The cost is
+/// linear in `t`, so the cheapest is at one end of the range of `t` that keeps both counts
+/// whole and non-negative.
+fn cheapest(particular: &[Rational], directions: &[Vec<Rational>]) -> Option<(i128, i128)> {
+    let [direction] = directions else {
+        // Neither button moves the claw, and the prize is where it starts.
+        return Some((0, 0));
+    };
+    // The free count is the last one: the pivot comes first unless button A doesn't move at all.
+    let free = direction.iter().rposition(|it| *it == Rational::ONE)?;
+    let (p, d) = (particular[1 - free], direction[1 - free]);
+    // The other count p + t * d is whole when t * d.num ≡ -p * q (mod q), for q = d.den. d.num
+    // and q are coprime, so that picks out one residue of t.
+    let q = d.den();
+    let pq = (p * q.into()).to_integer()?;
+    let inv = mod_inverse(d.num() % q, q)?;
+    let r = (-pq * inv).rem_euclid(q);
+    let lo = if d > Rational::ZERO {
+        (-p / d).ceil().max(0)
+    } else {
+        0
+    };
+    let hi = (d < Rational::ZERO).then(|| (-p / d).floor());
+    let presses = |t: i128| {
+        let other = (p + d * t.into()).to_integer()?;
+        let counts = if free == 0 { (t, other) } else { (other, t) };
+        (counts.0 >= 0 && counts.1 >= 0).then_some(counts)
+    };
+    let first = lo + (r - lo).rem_euclid(q);
+    let last = hi.map(|hi| hi - (hi - r).rem_euclid(q));
+    [Some(first), last]
+        .into_iter()
+        .flatten()
+        .filter_map(presses)
+        .min_by_key(|(a, b)| cost(*a, *b))
+}
+
+fn parse_input(inp: &str) -> Result<Vec<ClawGame>, AocError> {
     inp.lines()
-        .filter(|it| !it.is_empty())
-        .into_chunked::<3>()
-        .map(|chunk| ClawGame::parse(&chunk))
+        .enumerate()
+        .filter(|(_, it)| !it.is_empty())
+        .into_chunked_strict::<3>()
+        .map(|chunk| match chunk {
+            Ok(lines) => {
+                ClawGame::parse(lines.map(|(_, l)| l)).map_err(|e| e.offset_lines(lines[0].0))
+            }
+            Err(rest) => Err(AocError::invalid(format!(
+                "incomplete machine description ({} of 3 lines)",
+                rest.len()
+            ))
+            .at_line(rest[0].0 + 1)),
+        })
         .collect()
 }
 
-fn part1(inp: &str) -> i64 {
-    parse_input(inp)
+fn part1(inp: &str) -> Result<i64, AocError> {
+    Ok(parse_input(inp)?
         .into_iter()
         .filter_map(|game| game.solve().map(|it| it.cost))
-        .sum()
+        .sum())
 }
 
-fn part2(inp: &str) -> i64 {
-    parse_input(inp)
+fn part2(inp: &str) -> Result<i64, AocError> {
+    Ok(parse_input(inp)?
         .into_iter()
         .filter_map(|ClawGame { a, b, goal }| {
             let part2_game = ClawGame {
@@ -97,16 +161,23 @@ fn part2(inp: &str) -> i64 {
             };
             part2_game.solve().map(|it| it.cost)
         })
-        .sum()
+        .sum())
 }
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day13.txt").unwrap()
+impl solution::Solution for Day13 {
+    const DAY: u32 = 13;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        part1(inp).map(Answer::from)
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        part2(inp).map(Answer::from)
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+fn main() -> ExitCode {
+    solution::run::<Day13>()
 }
 
 #[cfg(test)]
@@ -170,4 +241,37 @@ mod tests {
         .solve();
         assert_eq!(res, None)
     }
+
+    #[test]
+    fn test_collinear() {
+        let game = |a: (i64, i64), b: (i64, i64), goal: (i64, i64)| ClawGame {
+            a: CoordDiff::from_xy(a.0, a.1),
+            b: CoordDiff::from_xy(b.0, b.1),
+            goal: Coord::from_xy(goal.0, goal.1),
+        };
+        let cost = |g: ClawGame| g.solve().map(|it| it.cost);
+        // A costs more per step than B, so only B is pressed.
+        assert_eq!(cost(game((2, 2), (1, 1), (10, 10))), Some(10));
+        // A is cheaper per step, but can't cover all of it.
+        assert_eq!(cost(game((6, 6), (1, 1), (13, 13))), Some(7));
+        assert_eq!(cost(game((2, 2), (4, 4), (7, 7))), None);
+        assert_eq!(cost(game((3, 0), (2, 0), (7, 0))), Some(5));
+        assert_eq!(cost(game((0, 0), (2, 3), (4, 6))), Some(2));
+        assert_eq!(cost(game((0, 0), (0, 0), (0, 0))), Some(0));
+        assert_eq!(cost(game((2, 2), (1, 1), (-4, -4))), None);
+        assert_eq!(cost(game((1, 1), (-1, -1), (3, 3))), Some(9));
+        let big = game((6, 6), (1, 1), (10000000000001, 10000000000001));
+        assert_eq!(cost(big), Some(5000000000003));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let inp = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\n\
+                   Button A: X+26, Y+66\nButton B: X+67, Y+21\n";
+        let err = parse_input(inp).unwrap_err();
+        assert_eq!(err.line, Some(5));
+        let err =
+            parse_input("Button A: X+94, Y+34\nButton C: X+22, Y+67\nPrize: X=1, Y=1").unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
 }
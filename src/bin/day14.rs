@@ -1,9 +1,13 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::ops::Add;
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Coord, CoordDiff};
+use adventofcode2024::error::{parse_lines, AocError};
+use adventofcode2024::numtheory::{crt, gcd, lcm};
 use adventofcode2024::parsers::{Parser, Parsers};
+use adventofcode2024::solution::{self, Answer, Solution};
 use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::layout::Layout;
 use ratatui::style::Color;
@@ -12,9 +16,12 @@ use ratatui::widgets::canvas::{Canvas, Context, Points};
 use ratatui::widgets::Block;
 use ratatui::Frame;
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day14.txt").unwrap()
-}
+struct Day14;
+
+const BOUNDS: CoordDiff = CoordDiff {
+    rows: 103,
+    cols: 101,
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct GuardRobot {
@@ -26,9 +33,19 @@ impl GuardRobot {
     fn pos_at(&self, t: i64, bounds: CoordDiff) -> Coord {
         (self.pos + (self.velocity * t)) % bounds
     }
+
+    /// How often the robot is back where it started: its column repeats every
+    /// `cols / gcd(velocity, cols)` steps, its row likewise, and both when those cycles line up.
+    fn period(&self, bounds: CoordDiff) -> i64 {
+        let cycle = |v: i64, size: i64| size / gcd(v, size);
+        lcm(
+            cycle(self.velocity.cols, bounds.cols),
+            cycle(self.velocity.rows, bounds.rows),
+        )
+    }
 }
 
-fn parse_inputs(inp: &str) -> Vec<GuardRobot> {
+fn parse_inputs(inp: &str) -> Result<Vec<GuardRobot>, AocError> {
     let psr = Parsers::lit("p=")
         .then(Parsers::snum())
         .followed_by(",")
@@ -41,7 +58,10 @@ fn parse_inputs(inp: &str) -> Vec<GuardRobot> {
             pos: Coord::from_xy(px, py),
             velocity: CoordDiff::from_xy(vx, vy),
         });
-    inp.lines().map(|l| psr.apply(l).unwrap_value()).collect()
+    parse_lines(inp, |l| {
+        psr.apply(l)
+            .value_or_error("a robot like `p=0,4 v=3,-3`", l)
+    })
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -137,19 +157,6 @@ fn score(positions: &[Coord], bounds: CoordDiff) -> usize {
     final_score
 }
 
-fn part1(inp: &str) -> usize {
-    let robots = parse_inputs(inp);
-    let bounds = CoordDiff {
-        rows: 103,
-        cols: 101,
-    };
-    let final_positions = robots
-        .into_iter()
-        .map(|r| r.pos_at(100, bounds))
-        .collect::<Vec<_>>();
-    score(&final_positions, bounds)
-}
-
 fn show_arrangement(positions: &HashSet<Coord>, bounds: CoordDiff, ctx: &mut Context<'_>) {
     for r in 0..bounds.rows {
         for c in 0..bounds.cols {
@@ -203,20 +210,59 @@ fn calc_pos_set(robots: &[GuardRobot], bounds: CoordDiff, n: usize) -> HashSet<C
         .collect::<HashSet<Coord>>()
 }
 
-fn part2(inp: &str) -> usize {
-    let robots = parse_inputs(inp);
-    let bounds = CoordDiff {
-        rows: 103,
-        cols: 101,
+// After some manual examination, I stumbled on n = 2597970 as looking very much like a tree but
+// not quite. It'll serve as a nice threshold for how compact the points are.
+fn tree_threshold(robots: &[GuardRobot], bounds: CoordDiff) -> i64 {
+    calculate_moment(&calc_pos_set(robots, bounds, 2597970))
+}
+
+/// How spread out `values` are: n² times their variance, which is exact in integers.
+fn spread(values: impl Iterator<Item = i64>) -> i64 {
+    let (n, sum, sum_sq) = values.fold((0, 0, 0), |(n, sum, sum_sq), v| {
+        (n + 1, sum + v, sum_sq + v * v)
+    });
+    n * sum_sq - sum * sum
+}
+
+/// The robots' columns go round every `cols` steps and their rows every `rows` steps, so the
+/// tightest grouping of columns happens at some `t ≡ tx (mod cols)` and of rows at some
+/// `t ≡ ty (mod rows)`. The tree is where both line up, which the Chinese Remainder Theorem gives
+/// directly.
+fn find_tree(robots: &[GuardRobot], bounds: CoordDiff) -> Result<i64, AocError> {
+    let tightest = |size: i64, axis: fn(Coord) -> i64| {
+        (0..size)
+            .min_by_key(|t| spread(robots.iter().map(|r| axis(r.pos_at(*t, bounds)))))
+            .unwrap_or(0)
     };
+    let tx = tightest(bounds.cols, |c| c.col);
+    let ty = tightest(bounds.rows, |c| c.row);
+    let (t, _) = crt(&[(tx, bounds.cols), (ty, bounds.rows)]).ok_or_else(|| {
+        AocError::invalid(format!(
+            "columns are tightest at t ≡ {tx} (mod {}) and rows at t ≡ {ty} (mod {}), which never \
+             happen together",
+            bounds.cols, bounds.rows
+        ))
+    })?;
+    let period = robots.iter().map(|r| r.period(bounds)).fold(1, lcm);
+    let moment = calculate_moment(&calc_pos_set(robots, bounds, t as usize));
+    let threshold = tree_threshold(robots, bounds);
+    if moment > threshold {
+        return Err(AocError::invalid(format!(
+            "the most compact arrangement (n = {t}, moment {moment}) isn't compact enough"
+        )));
+    }
+    // Robots that go round faster than the whole grid could make the tree repeat sooner.
+    Ok(t % period)
+}
+
+// Interactive viewer for looking through arrangements by hand; run with `--explore`.
+fn explore(robots: &[GuardRobot], bounds: CoordDiff) -> usize {
     let mut terminal = ratatui::init();
     let mut n = 0;
     let mut force_show = false;
-    // After some manual examination, I stumbled on n = 2597970 as looking very much like a tree but
-    // not quite. It'll serve as a nice threshold for how compact the points are.
-    let threshold = calculate_moment(&calc_pos_set(&robots, bounds, 2597970));
-    loop {
-        let pos_set = calc_pos_set(&robots, bounds, n);
+    let threshold = tree_threshold(robots, bounds);
+    let chosen = loop {
+        let pos_set = calc_pos_set(robots, bounds, n);
         let is_maybe_tree = calculate_moment(&pos_set) <= threshold;
         if n % 100000 == 0 || force_show || is_maybe_tree {
             terminal
@@ -246,7 +292,7 @@ fn part2(inp: &str) -> usize {
                     KeyCode::Char('i') => n -= 100_000_000,
                     KeyCode::Char('9') => n += 1_000_000_000,
                     KeyCode::Char('o') => n -= 1_000_000_000,
-                    KeyCode::Char('x') => return n,
+                    KeyCode::Char('x') => break n,
                     _ => {
                         force_show = false;
                         n += 1;
@@ -257,12 +303,41 @@ fn part2(inp: &str) -> usize {
         } else {
             n += 1;
         }
+    };
+    ratatui::restore();
+    chosen
+}
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        let final_positions = parse_inputs(inp)?
+            .into_iter()
+            .map(|r| r.pos_at(100, BOUNDS))
+            .collect::<Vec<_>>();
+        Ok(score(&final_positions, BOUNDS).into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(find_tree(&parse_inputs(inp)?, BOUNDS)?.into())
     }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+fn main() -> ExitCode {
+    if std::env::args().any(|it| it == "--explore") {
+        return match Day14::input().and_then(|inp| parse_inputs(&inp)) {
+            Ok(robots) => {
+                println!("Part 2: {}", explore(&robots, BOUNDS));
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    solution::run::<Day14>()
 }
 
 #[cfg(test)]
@@ -280,6 +355,24 @@ mod tests {
             Coord::from_xy(7, 7)
         );
     }
+    #[test]
+    fn test_period() {
+        let bounds = CoordDiff::from_xy(10, 9);
+        let r = GuardRobot {
+            pos: Coord::from_xy(1, 1),
+            velocity: CoordDiff::from_xy(2, -3),
+        };
+        // Columns repeat every 5 steps and rows every 3.
+        assert_eq!(r.period(bounds), 15);
+        assert_eq!(r.pos_at(15, bounds), r.pos);
+        assert_ne!(r.pos_at(5, bounds), r.pos);
+        let still = GuardRobot {
+            velocity: CoordDiff::from_xy(0, 0),
+            ..r
+        };
+        assert_eq!(still.period(bounds), 1);
+    }
+
     #[test]
     fn test_mod_large_vel() {
         let r = GuardRobot {
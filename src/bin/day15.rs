@@ -1,7 +1,12 @@
 use std::collections::HashSet;
 use std::ops::Add;
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Coord, CoordDiff};
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, read_input, Answer, Solution};
+
+struct Day15;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 enum EntityKind {
@@ -155,11 +160,9 @@ impl Map {
             MoveResult::Blocked => (),
             MoveResult::Ok(m) => {
                 if source.kind != EntityKind::Robot {
-                    self.entities
-                        .iter_mut()
-                        .find(|it| **it == source)
-                        .map(|e| e.apply_move(m))
-                        .unwrap();
+                    if let Some(e) = self.entities.iter_mut().find(|it| **it == source) {
+                        e.apply_move(m);
+                    }
                 }
             }
         }
@@ -182,7 +185,11 @@ impl Map {
     }
 }
 
-fn parse_inputs(map_inp: &str, move_inp: &str) -> (Map, Vec<Move>) {
+fn parse_inputs(inp: &str) -> Result<(Map, Vec<Move>), AocError> {
+    let (map_inp, move_inp) = inp
+        .split_once("\n\n")
+        .ok_or_else(|| AocError::invalid("expected a map and moves separated by a blank line"))?;
+    let mut robot = None;
     let mut map = Map {
         robot: Coord::from_xy(-1, -1),
         entities: Default::default(),
@@ -208,33 +215,42 @@ fn parse_inputs(map_inp: &str, move_inp: &str) -> (Map, Vec<Move>) {
                         extent: CoordDiff::from_xy(1, 1),
                     });
                 }
-                '@' => map.robot = coord,
-                _ => (),
+                '@' => {
+                    if robot.replace(coord).is_some() {
+                        return Err(AocError::invalid("more than one robot")
+                            .at_line(ri + 1)
+                            .at_column(ci + 1));
+                    }
+                }
+                '.' => (),
+                other => {
+                    return Err(AocError::parse("one of #O@.", other)
+                        .at_line(ri + 1)
+                        .at_column(ci + 1))
+                }
             }
         }
     }
-    assert!(map.robot != Coord::from_xy(-1, -1));
-
-    let moves = move_inp
-        .trim()
-        .chars()
-        .map(|c| match c {
-            '>' => Move::Right,
-            '<' => Move::Left,
-            '^' => Move::Up,
-            'v' => Move::Down,
-            other => panic!("Unexpected character: {other}"),
-        })
-        .collect();
-    (map, moves)
-}
+    map.robot = robot.ok_or_else(|| AocError::invalid("no robot on the map"))?;
 
-fn map_input() -> String {
-    std::fs::read_to_string("./inputs/day15_map.txt").unwrap()
-}
-
-fn move_input() -> String {
-    std::fs::read_to_string("./inputs/day15_moves.txt").unwrap()
+    let map_lines = map_inp.lines().count() + 1;
+    let mut moves = vec![];
+    for (li, l) in move_inp.lines().enumerate() {
+        for (ci, c) in l.chars().enumerate() {
+            moves.push(match c {
+                '>' => Move::Right,
+                '<' => Move::Left,
+                '^' => Move::Up,
+                'v' => Move::Down,
+                other => {
+                    return Err(AocError::parse("one of <>^v", other)
+                        .at_line(map_lines + li + 1)
+                        .at_column(ci + 1))
+                }
+            });
+        }
+    }
+    Ok((map, moves))
 }
 
 #[allow(dead_code)]
@@ -266,16 +282,16 @@ fn draw(map: &Map) {
     }
 }
 
-fn part1(map_inp: &str, move_inp: &str) -> i64 {
-    let (mut map, moves) = parse_inputs(map_inp, move_inp);
+fn part1(inp: &str) -> Result<i64, AocError> {
+    let (mut map, moves) = parse_inputs(inp)?;
     for mv in moves {
         map.apply_move_and_update_robot(mv);
     }
-    map.gps_coord_sum()
+    Ok(map.gps_coord_sum())
 }
 
-fn part2(map_inp: &str, move_inp: &str) -> i64 {
-    let (mut map, moves) = parse_inputs(map_inp, move_inp);
+fn part2(inp: &str) -> Result<i64, AocError> {
+    let (mut map, moves) = parse_inputs(inp)?;
     for e in map.entities.iter_mut() {
         e.extent = CoordDiff { rows: 1, cols: 2 };
         e.pos = Coord {
@@ -290,10 +306,27 @@ fn part2(map_inp: &str, move_inp: &str) -> i64 {
     for mv in moves {
         map.apply_move_and_update_robot(mv);
     }
-    map.gps_coord_sum()
+    Ok(map.gps_coord_sum())
+}
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    fn input() -> Result<String, AocError> {
+        let map = read_input("day15_map.txt")?;
+        let moves = read_input("day15_moves.txt")?;
+        Ok(format!("{}\n\n{}", map.trim_end(), moves))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp)?.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(part2(inp)?.into())
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&map_input(), &move_input()));
-    println!("Part 2: {}", part2(&map_input(), &move_input()));
+fn main() -> ExitCode {
+    solution::run::<Day15>()
 }
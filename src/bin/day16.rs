@@ -1,10 +1,11 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Bounded, Coord, CoordDiff};
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
 
-fn input() -> String {
-    std::fs::read_to_string("./inputs/day16.txt").unwrap()
-}
+struct Day16;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 enum Direction {
@@ -26,7 +27,7 @@ trait MazeState {
         DIRS.into_iter()
             .map(|d| self.score_for(inc, d))
             .min()
-            .unwrap()
+            .unwrap_or(i64::MAX)
     }
 }
 
@@ -58,7 +59,9 @@ impl Bounded for Maze {
     }
 }
 
-fn parse_input(inp: &str) -> Maze {
+fn parse_input(inp: &str) -> Result<Maze, AocError> {
+    let mut start = None;
+    let mut end = None;
     let mut maze = Maze {
         start: Coord { row: -1, col: -1 },
         end: Coord { row: -1, col: -1 },
@@ -76,9 +79,20 @@ fn parse_input(inp: &str) -> Maze {
                 '#' => {
                     maze.walls.insert(coord);
                 }
-                'S' => maze.start = coord,
-                'E' => maze.end = coord,
-                _ => (),
+                'S' | 'E' => {
+                    let slot = if c == 'S' { &mut start } else { &mut end };
+                    if slot.replace(coord).is_some() {
+                        return Err(AocError::invalid(format!("more than one {c}"))
+                            .at_line(ri + 1)
+                            .at_column(ci + 1));
+                    }
+                }
+                '.' => (),
+                other => {
+                    return Err(AocError::parse("one of #.SE", other)
+                        .at_line(ri + 1)
+                        .at_column(ci + 1))
+                }
             }
             size = CoordDiff {
                 rows: ri as i64 + 1,
@@ -87,22 +101,25 @@ fn parse_input(inp: &str) -> Maze {
         }
     }
     maze.size = size;
-    assert!(maze.start.row != -1);
-    assert!(maze.end.row != -1);
-    maze
+    maze.start = start.ok_or_else(|| AocError::invalid("no start (S) in the maze"))?;
+    maze.end = end.ok_or_else(|| AocError::invalid("no end (E) in the maze"))?;
+    Ok(maze)
 }
 
-fn forward_dir_between(from: Coord, to: Coord) -> Direction {
+fn forward_dir_between(from: Coord, to: Coord) -> Result<Direction, AocError> {
     if to == from.u() {
-        North
+        Ok(North)
     } else if to == from.d() {
-        South
+        Ok(South)
     } else if to == from.l() {
-        West
+        Ok(West)
     } else if to == from.r() {
-        East
+        Ok(East)
     } else {
-        panic!("{from:?} -> {to:?} is not a 4-connected move");
+        Err(AocError::invalid(format!(
+            "({}, {}) -> ({}, {}) is not a 4-connected move",
+            from.row, from.col, to.row, to.col
+        )))
     }
 }
 
@@ -111,7 +128,7 @@ fn solve_backwards(
     states: &mut HashMap<Coord, Box<dyn MazeState>>,
     queue: &mut VecDeque<Coord>,
     maze: &Maze,
-) {
+) -> Result<(), AocError> {
     let is_start = from == maze.start;
     // Consider neighbors that are not walls; partition into already solved and not already solved.
     let (solved, unsolved): (Vec<_>, Vec<_>) = from
@@ -120,7 +137,12 @@ fn solve_backwards(
         .partition(|it| states.contains_key(it));
 
     // We did something wrong if we got here and don't have any solved neighbors.
-    assert!(!solved.is_empty());
+    if solved.is_empty() {
+        return Err(AocError::invalid(format!(
+            "({}, {}) was queued without a solved neighbor",
+            from.row, from.col
+        )));
+    }
 
     if !is_start {
         for neighbor in unsolved {
@@ -129,8 +151,8 @@ fn solve_backwards(
     }
     let mut statemap = MazeStateMap::new();
 
-    solved.iter().copied().for_each(|it| {
-        let dir = forward_dir_between(from, it);
+    for it in solved.iter().copied() {
+        let dir = forward_dir_between(from, it)?;
         let its_soln = &states[&it];
         let target_score = its_soln.best_score_for(dir);
         for inc in DIRS {
@@ -142,7 +164,7 @@ fn solve_backwards(
                 })
                 .or_insert(HashMap::from([(dir, target_score.saturating_add(offset))]));
         }
-    });
+    }
 
     if let Some(curr_state) = states.get(&from) {
         if DIRS.into_iter().any(|d_in| {
@@ -161,10 +183,13 @@ fn solve_backwards(
             queue.push_back(neighbor);
         }
     }
+    Ok(())
 }
 
-fn part1(inp: &str) -> (i64, HashMap<Coord, Box<dyn MazeState>>) {
-    let maze = parse_input(inp);
+type Solved = (i64, HashMap<Coord, Box<dyn MazeState>>);
+
+fn part1(inp: &str) -> Result<Solved, AocError> {
+    let maze = parse_input(inp)?;
 
     let initial_state = 0i64;
     let mut states: HashMap<Coord, Box<dyn MazeState>> = HashMap::new();
@@ -176,11 +201,14 @@ fn part1(inp: &str) -> (i64, HashMap<Coord, Box<dyn MazeState>>) {
         }
     }
     while let Some(next_pos) = queue.pop_front() {
-        solve_backwards(next_pos, &mut states, &mut queue, &maze);
+        solve_backwards(next_pos, &mut states, &mut queue, &maze)?;
     }
 
-    let best_score = states[&maze.start].best_score_for(Direction::East);
-    (best_score, states)
+    let best_score = states
+        .get(&maze.start)
+        .ok_or_else(|| AocError::invalid("there's no path from the start to the end"))?
+        .best_score_for(Direction::East);
+    Ok((best_score, states))
 }
 
 fn traverse_all_best_paths(
@@ -189,41 +217,64 @@ fn traverse_all_best_paths(
     maze: &Maze,
     state: &HashMap<Coord, Box<dyn MazeState>>,
     seen: &mut HashSet<Coord>,
-) {
+) -> Result<(), AocError> {
     seen.insert(from);
     if from == maze.end {
-        return;
+        return Ok(());
     }
     let mut candidates = from
         .iter_neighbors::<_, 4>(maze)
         .filter(|it| !seen.contains(it))
         .filter(|it| state.contains_key(it))
         .map(|it| {
-            (
+            Ok((
                 it,
-                state[&from].score_for(dir, forward_dir_between(from, it)),
-            )
+                state[&from].score_for(dir, forward_dir_between(from, it)?),
+            ))
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, AocError>>()?;
     candidates.sort_by_key(|(_, s)| *s);
-    if candidates.is_empty() {
-        return;
-    }
-    let best_score = candidates.first().unwrap().1;
+    let Some(&(_, best_score)) = candidates.first() else {
+        return Ok(());
+    };
     for (c, _) in candidates.into_iter().filter(|(_, s)| *s == best_score) {
-        traverse_all_best_paths(c, forward_dir_between(from, c), maze, state, seen);
+        traverse_all_best_paths(c, forward_dir_between(from, c)?, maze, state, seen)?;
     }
+    Ok(())
 }
 
-fn part2(inp: &str) -> i64 {
-    let (_, soln) = part1(inp);
-    let maze = parse_input(inp);
+fn part2(inp: &str) -> Result<usize, AocError> {
+    let (_, soln) = part1(inp)?;
+    let maze = parse_input(inp)?;
     let mut seen = HashSet::new();
-    traverse_all_best_paths(maze.start, East, &maze, &soln, &mut seen);
-    seen.len() as i64
+    traverse_all_best_paths(maze.start, East, &maze, &soln, &mut seen)?;
+    Ok(seen.len())
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&input()).0);
-    println!("Part 2: {}", part2(&input()));
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp)?.0.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(part2(inp)?.into())
+    }
+}
+
+fn main() -> ExitCode {
+    solution::run::<Day16>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_dir_between() {
+        let c = Coord { row: 1, col: 1 };
+        assert_eq!(forward_dir_between(c, c.r()), Ok(East));
+        assert!(forward_dir_between(c, c.dr()).is_err());
+    }
 }
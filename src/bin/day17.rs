@@ -1,3 +1,11 @@
+use std::process::ExitCode;
+
+use adventofcode2024::error::{parse_as, AocError};
+use adventofcode2024::progress::Progress;
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day17;
+
 enum Instruction {
     Adv,
     Bxl,
@@ -11,7 +19,7 @@ enum Instruction {
 
 use Instruction::*;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 struct Machine {
     instruction_ptr: usize,
     reg_a: usize,
@@ -20,19 +28,28 @@ struct Machine {
     output_buffer: Vec<usize>,
 }
 
-fn combo_op(op: usize, machine: &Machine) -> usize {
+fn combo_op(op: usize, machine: &Machine) -> Result<usize, AocError> {
     match op {
-        0..=3 => op,
-        4 => machine.reg_a,
-        5 => machine.reg_b,
-        6 => machine.reg_c,
-        other => panic!("Invalid combo op {other}"),
+        0..=3 => Ok(op),
+        4 => Ok(machine.reg_a),
+        5 => Ok(machine.reg_b),
+        6 => Ok(machine.reg_c),
+        other => Err(AocError::invalid(format!("invalid combo operand {other}"))),
     }
 }
 
+/// `a / 2^combo`, which is 0 once the shift is past every bit of `a`.
+fn dv(a: usize, op: usize, machine: &Machine) -> Result<usize, AocError> {
+    let shift = combo_op(op, machine)?;
+    Ok(u32::try_from(shift)
+        .ok()
+        .and_then(|it| a.checked_shr(it))
+        .unwrap_or(0))
+}
+
 impl Instruction {
-    fn from_code(code: usize) -> Instruction {
-        match code {
+    fn from_code(code: usize) -> Result<Instruction, AocError> {
+        Ok(match code {
             0 => Adv,
             1 => Bxl,
             2 => Bst,
@@ -41,18 +58,18 @@ impl Instruction {
             5 => Out,
             6 => Bdv,
             7 => Cdv,
-            other => panic!("Invalid opcode {other}"),
-        }
+            other => return Err(AocError::invalid(format!("invalid opcode {other}"))),
+        })
     }
 }
 
 impl Machine {
-    fn eval_one(&mut self, code: &[usize]) {
-        let ins = Instruction::from_code(code[self.instruction_ptr]);
+    fn eval_one(&mut self, code: &[usize]) -> Result<(), AocError> {
+        let ins = Instruction::from_code(code[self.instruction_ptr])?;
         let op = code[self.instruction_ptr + 1];
         match ins {
             Adv => {
-                self.reg_a /= 2usize.pow(combo_op(op, self) as u32);
+                self.reg_a = dv(self.reg_a, op, self)?;
                 self.instruction_ptr += 2;
             }
             Bxl => {
@@ -60,7 +77,7 @@ impl Machine {
                 self.instruction_ptr += 2;
             }
             Bst => {
-                self.reg_b = combo_op(op, self) % 8;
+                self.reg_b = combo_op(op, self)? % 8;
                 self.instruction_ptr += 2;
             }
             Jnz => {
@@ -75,34 +92,45 @@ impl Machine {
                 self.instruction_ptr += 2;
             }
             Out => {
-                self.output_buffer.push(combo_op(op, self) % 8);
+                self.output_buffer.push(combo_op(op, self)? % 8);
                 self.instruction_ptr += 2;
             }
             Bdv => {
-                self.reg_b = self.reg_a / 2usize.pow(combo_op(op, self) as u32);
+                self.reg_b = dv(self.reg_a, op, self)?;
                 self.instruction_ptr += 2;
             }
             Cdv => {
-                self.reg_c = self.reg_a / 2usize.pow(combo_op(op, self) as u32);
+                self.reg_c = dv(self.reg_a, op, self)?;
                 self.instruction_ptr += 2;
             }
         }
+        Ok(())
     }
-    fn eval_program(&mut self, code: &[usize], early_term: bool) {
+    fn eval_program(&mut self, code: &[usize], early_term: bool) -> Result<(), AocError> {
         while self.instruction_ptr + 1 < code.len() {
-            self.eval_one(code);
-            if early_term && self.output_buffer.len() > code.len() {
-                return;
-            } else if early_term
-                && self
-                    .output_buffer
-                    .iter()
-                    .enumerate()
-                    .any(|(idx, val)| *val != code[idx])
+            self.eval_one(code)?;
+            if early_term
+                && (self.output_buffer.len() > code.len()
+                    || self
+                        .output_buffer
+                        .iter()
+                        .enumerate()
+                        .any(|(idx, val)| *val != code[idx]))
             {
-                return;
+                return Ok(());
             }
         }
+        Ok(())
+    }
+    /// Runs the program, ticking `progress` at every instruction so a program that never halts
+    /// can still be stopped. Stops early once there's more output than program, since that can't
+    /// be a copy of it.
+    fn eval_checked(&mut self, code: &[usize], progress: &mut Progress) -> Result<(), AocError> {
+        while self.instruction_ptr + 1 < code.len() && self.output_buffer.len() <= code.len() {
+            progress.tick()?;
+            self.eval_one(code)?;
+        }
+        Ok(())
     }
     fn prepare_output(&self) -> String {
         self.output_buffer
@@ -113,72 +141,136 @@ impl Machine {
     }
 }
 
-fn part1(mut machine: Machine, program: &[usize]) -> String {
-    machine.eval_program(program, false);
-    machine.prepare_output()
+fn part1(mut machine: Machine, program: &[usize]) -> Result<String, AocError> {
+    machine.eval_program(program, false)?;
+    Ok(machine.prepare_output())
 }
 
-fn part2(machine: Machine, program: &[usize]) -> usize {
-    for reg_a_val in 0..usize::MAX {
-        if reg_a_val % 100_000_000 == 0 {
-            println!("{reg_a_val}");
-        }
+/// The smallest value of register A that makes the program print itself.
+///
+/// Programs like this one loop until A is 0, printing one value per pass based on A and shifting
+/// it right by 3 bits, so the last value printed only depends on A's top 3 bits, the one before
+/// on the top 6, and so on. A can be built from the top down, 3 bits at a time, keeping each
+/// choice whose output matches the end of the program, and backtracking if it leads nowhere.
+fn find_quine(
+    machine: &Machine,
+    program: &[usize],
+    progress: &mut Progress,
+) -> Result<usize, AocError> {
+    let found = extend_quine(machine, program, 0, program.len(), progress)?;
+    progress.finish();
+    found.ok_or_else(|| AocError::invalid("no value of register A reproduces the program"))
+}
+
+/// Tries each 3-bit extension of `a` that prints the last `program.len() - matched + 1` values.
+fn extend_quine(
+    machine: &Machine,
+    program: &[usize],
+    a: usize,
+    matched: usize,
+    progress: &mut Progress,
+) -> Result<Option<usize>, AocError> {
+    let Some(next) = matched.checked_sub(1) else {
+        return Ok(Some(a));
+    };
+    for bits in 0..8 {
+        let Some(cand) = a.checked_mul(8).map(|it| it + bits) else {
+            return Ok(None);
+        };
         let mut my_machine = machine.clone();
-        my_machine.reg_a = reg_a_val;
-        my_machine.eval_program(program, true);
-        if &my_machine.output_buffer == program {
-            return reg_a_val;
+        my_machine.reg_a = cand;
+        my_machine.eval_checked(program, progress)?;
+        if my_machine.output_buffer != program[next..] {
+            continue;
+        }
+        progress.note(format!(
+            "a = {cand} prints the last {} values",
+            program.len() - next
+        ));
+        if let Some(found) = extend_quine(machine, program, cand, next, progress)? {
+            return Ok(Some(found));
         }
     }
-    panic!("Did not find a solution.");
+    Ok(None)
 }
 
-fn input_machine() -> Machine {
-    Machine {
-        reg_a: 32916674,
-        reg_b: 0,
-        reg_c: 0,
+const INPUT: &str = "\
+Register A: 32916674
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,1,7,5,0,3,1,4,4,0,5,5,3,0";
+
+fn parse_inputs(inp: &str) -> Result<(Machine, Vec<usize>), AocError> {
+    let mut lines = inp.lines().enumerate();
+    let mut register = |name: &str| -> Result<usize, AocError> {
+        let (idx, l) = lines
+            .next()
+            .ok_or_else(|| AocError::invalid(format!("missing register {name}")))?;
+        let prefix = format!("Register {name}: ");
+        l.strip_prefix(&prefix)
+            .ok_or_else(|| AocError::parse(format!("`{prefix}<value>`"), l))
+            .and_then(|it| parse_as(it, "an integer"))
+            .map_err(|e| e.at_line(idx + 1))
+    };
+    let machine = Machine {
+        reg_a: register("A")?,
+        reg_b: register("B")?,
+        reg_c: register("C")?,
         instruction_ptr: 0,
         output_buffer: vec![],
-    }
+    };
+    let (idx, l) = lines
+        .find(|(_, l)| !l.is_empty())
+        .ok_or_else(|| AocError::invalid("missing program"))?;
+    let program = l
+        .strip_prefix("Program: ")
+        .ok_or_else(|| AocError::parse("`Program: <codes>`", l))
+        .and_then(|it| {
+            it.split(',')
+                .map(|code| parse_as::<usize>(code, "a 3-bit number").and_then(check_3bit))
+                .collect()
+        })
+        .map_err(|e| e.at_line(idx + 1))?;
+    Ok((machine, program))
 }
 
-fn input_program() -> [usize; 16] {
-    [2, 4, 1, 1, 7, 5, 0, 3, 1, 4, 4, 0, 5, 5, 3, 0]
+fn check_3bit(code: usize) -> Result<usize, AocError> {
+    if code < 8 {
+        Ok(code)
+    } else {
+        Err(AocError::parse("a 3-bit number", code.to_string()))
+    }
 }
 
-fn calc_b_mod_8(prev_a: usize) -> usize {
-    let mut b = prev_a % 8;
-    b ^= 1;
-    let c = prev_a / (2usize.pow(b as u32));
-    b ^= 4;
-    b ^= c;
-    b % 8
-}
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+
+    fn input() -> Result<String, AocError> {
+        Ok(INPUT.to_string())
+    }
 
-// Not actually general in the input program, since the program is hard-coded in calc_b_mod_8.
-fn walk_backwards(input_program: &[usize], init_a: usize) -> usize {
-    let mut a = init_a;
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        let (machine, program) = parse_inputs(inp)?;
+        Ok(part1(machine, &program)?.into())
+    }
 
-    for bm8 in input_program.iter().rev() {
-        for a_cand in (a * 8)..usize::MAX {
-            let cand_bm8 = calc_b_mod_8(a_cand);
-            if cand_bm8 == *bm8 {
-                a = a_cand;
-                break;
-            }
-        }
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        let (machine, program) = parse_inputs(inp)?;
+        Ok(find_quine(&machine, &program, &mut Progress::new("day 17 part 2"))?.into())
     }
-    a
 }
 
-fn main() {
-    println!("Part 1: {}", part1(input_machine(), &input_program()));
-    println!("Part 2: {}", walk_backwards(&input_program(), 0));
+fn main() -> ExitCode {
+    solution::run::<Day17>()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use adventofcode2024::error::ErrorKind;
+
     use super::*;
     #[test]
     fn test_p1() {
@@ -190,7 +282,7 @@ mod tests {
             output_buffer: vec![],
         };
         let program = [0, 1, 5, 4, 3, 0];
-        machine.eval_program(&program, false);
+        machine.eval_program(&program, false).unwrap();
         assert_eq!(machine.output_buffer, vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
     }
     #[test]
@@ -203,7 +295,61 @@ mod tests {
             output_buffer: vec![],
         };
         let program = [0, 3, 5, 4, 3, 0];
-        let reg_a_val = part2(machine, &program);
-        assert_eq!(reg_a_val, 117440);
+        let reg_a_val = find_quine(&machine, &program, &mut Progress::silent());
+        assert_eq!(reg_a_val, Ok(117440));
+    }
+
+    #[test]
+    fn test_parse() {
+        let (machine, program) = parse_inputs(INPUT).unwrap();
+        assert_eq!(machine.reg_a, 32916674);
+        assert_eq!(program.len(), 16);
+        assert_eq!(part1(machine, &program).unwrap(), "7,1,2,3,2,6,7,2,5");
+        let err =
+            parse_inputs("Register A: 1\nRegister B: x\nRegister C: 0\n\nProgram: 0").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        let err = parse_inputs("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,9")
+            .unwrap_err();
+        assert_eq!(err.line, Some(5));
+    }
+
+    #[test]
+    fn test_p2_real_input() {
+        let (machine, program) = parse_inputs(INPUT).unwrap();
+        let a = find_quine(&machine, &program, &mut Progress::silent()).unwrap();
+        assert_eq!(a, 202356708354602);
+        let mut check = machine.clone();
+        check.reg_a = a;
+        check.eval_program(&program, false).unwrap();
+        assert_eq!(check.output_buffer, program);
+    }
+
+    #[test]
+    fn test_p2_no_quine() {
+        // Never prints anything.
+        let (machine, program) =
+            parse_inputs("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,3,0")
+                .unwrap();
+        let err = find_quine(&machine, &program, &mut Progress::silent()).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Invalid(..)));
+    }
+
+    #[test]
+    fn test_p2_runaway() {
+        let parse = |program: &str| {
+            parse_inputs(&format!(
+                "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {program}"
+            ))
+            .unwrap()
+        };
+        // Prints A forever without shifting it; the runs are cut short once they print too much.
+        let (machine, program) = parse("5,4,3,0");
+        let err = find_quine(&machine, &program, &mut Progress::silent()).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Invalid(..)));
+        // Loops forever without printing anything, so only the budget stops it.
+        let (machine, program) = parse("3,0");
+        let mut progress = Progress::silent().with_budget(Duration::from_millis(50));
+        let err = find_quine(&machine, &program, &mut progress).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Interrupted(..)));
     }
 }
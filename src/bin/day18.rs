@@ -1,21 +1,25 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Bounded, Coord};
+use adventofcode2024::error::{parse_as, parse_lines, AocError};
+use adventofcode2024::solution::{self, Answer, Solution};
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day18.txt").unwrap()
-}
+struct Day18;
 
-fn parse_inputs(inp: &str) -> Vec<Coord> {
-    inp.lines()
-        .map(|l| {
-            let parts = l.split(",").collect::<Vec<_>>();
-            Coord {
-                row: parts[1].parse().unwrap(),
-                col: parts[0].parse().unwrap(),
-            }
+/// How many bytes have fallen by the time part 1 looks at the memory space.
+const FALLEN: usize = 1024;
+
+fn parse_inputs(inp: &str) -> Result<Vec<Coord>, AocError> {
+    parse_lines(inp, |l| {
+        let (x, y) = l
+            .split_once(",")
+            .ok_or_else(|| AocError::parse("a position like `5,4`", l))?;
+        Ok(Coord {
+            row: parse_as(y, "an integer")?,
+            col: parse_as(x, "an integer")?,
         })
-        .collect()
+    })
 }
 
 struct Maze {
@@ -59,25 +63,46 @@ fn solve(maze: &Maze) -> Option<usize> {
     scores.get(&start).copied()
 }
 
-fn part1(inp: &str) -> usize {
-    let blocks: HashSet<Coord> = HashSet::from_iter(parse_inputs(inp)[0..1024].iter().copied());
-    let maze = Maze { blocks };
-    solve(&maze).unwrap()
+fn part1(inp: &str) -> Result<usize, AocError> {
+    let input_coords = parse_inputs(inp)?;
+    let fallen = input_coords.get(0..FALLEN).ok_or_else(|| {
+        AocError::invalid(format!(
+            "only {} bytes fall, not {FALLEN}",
+            input_coords.len()
+        ))
+    })?;
+    let maze = Maze {
+        blocks: HashSet::from_iter(fallen.iter().copied()),
+    };
+    solve(&maze).ok_or_else(|| AocError::invalid("the exit can't be reached"))
 }
 
-fn part2(inp: &str) -> Coord {
-    let input_coords = parse_inputs(inp);
-    for b in 1025..=input_coords.len() {
+fn part2(inp: &str) -> Result<Coord, AocError> {
+    let input_coords = parse_inputs(inp)?;
+    for b in FALLEN + 1..=input_coords.len() {
         let blocks: HashSet<Coord> = HashSet::from_iter(input_coords[0..b].iter().copied());
         let maze = Maze { blocks };
         if solve(&maze).is_none() {
-            return input_coords[b - 1];
+            return Ok(input_coords[b - 1]);
         }
     }
-    panic!("No solution found.");
+    Err(AocError::invalid(
+        "the exit can still be reached after every byte falls",
+    ))
+}
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp)?.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(format!("{:?}", part2(inp)?).into())
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {:?}", part2(&inputs()));
+fn main() -> ExitCode {
+    solution::run::<Day18>()
 }
@@ -1,26 +1,37 @@
 use std::collections::HashMap;
+use std::process::ExitCode;
 
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, read_input, Answer, Solution};
 use regex::Regex;
 
-fn pattern_input() -> String {
-    std::fs::read_to_string("./inputs/day19_patterns.txt").unwrap()
-}
+struct Day19;
 
-fn design_input() -> String {
-    std::fs::read_to_string("./inputs/day19_designs.txt").unwrap()
+/// Splits the input into the towel patterns on the first line and the designs after the blank
+/// line.
+fn split_inputs(inp: &str) -> Result<(Vec<&str>, &str), AocError> {
+    let (patterns, designs) = inp.split_once("\n\n").ok_or_else(|| {
+        AocError::invalid("expected patterns and designs separated by a blank line")
+    })?;
+    let patterns: Vec<&str> = patterns.trim().split(", ").collect();
+    if let Some(bad) = patterns
+        .iter()
+        .find(|it| it.is_empty() || !it.chars().all(|c| c.is_ascii_lowercase()))
+    {
+        return Err(AocError::parse("a pattern of stripe colours like `bwu`", *bad).at_line(1));
+    }
+    Ok((patterns, designs))
 }
 
-fn parse_patterns(inp: &str) -> Regex {
-    Regex::new(&format!(
-        "^({})+$",
-        inp.split(", ").collect::<Vec<_>>().join("|")
-    ))
-    .unwrap()
+fn parse_patterns(patterns: &[&str]) -> Result<Regex, AocError> {
+    Regex::new(&format!("^({})+$", patterns.join("|")))
+        .map_err(|e| AocError::invalid(format!("the patterns don't make a valid regex: {e}")))
 }
 
-fn part1(patterns: &str, designs: &str) -> usize {
-    let re = parse_patterns(patterns);
-    designs.lines().filter(|it| re.is_match(it)).count()
+fn part1(inp: &str) -> Result<usize, AocError> {
+    let (patterns, designs) = split_inputs(inp)?;
+    let re = parse_patterns(&patterns)?;
+    Ok(designs.lines().filter(|it| re.is_match(it)).count())
 }
 
 fn count_matches<'a>(
@@ -36,26 +47,43 @@ fn count_matches<'a>(
     }
     let mut total = 0;
     for p in patterns {
-        if design.starts_with(p) {
-            total += count_matches(patterns, &design[p.len()..], cache)
+        if let Some(rest) = design.strip_prefix(p) {
+            total += count_matches(patterns, rest, cache)
         }
     }
     cache.insert(design, total);
     total
 }
 
-fn part2(patterns: &str, designs: &str) -> usize {
-    let patterns = patterns.split(", ").collect::<Vec<_>>();
+fn part2(inp: &str) -> Result<usize, AocError> {
+    let (patterns, designs) = split_inputs(inp)?;
     let mut cache = HashMap::new();
-    designs
+    Ok(designs
         .lines()
         .map(|l| count_matches(&patterns, l, &mut cache))
-        .sum()
+        .sum())
+}
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+
+    fn input() -> Result<String, AocError> {
+        let patterns = read_input("day19_patterns.txt")?;
+        let designs = read_input("day19_designs.txt")?;
+        Ok(format!("{}\n\n{}", patterns.trim_end(), designs))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp)?.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(part2(inp)?.into())
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&pattern_input(), &design_input()));
-    println!("Part 2: {}", part2(&pattern_input(), &design_input()));
+fn main() -> ExitCode {
+    solution::run::<Day19>()
 }
 
 #[cfg(test)]
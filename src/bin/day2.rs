@@ -1,111 +1,224 @@
-use std::cmp::Ordering;
+use std::fmt::Display;
+use std::process::ExitCode;
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day2.txt").unwrap()
+use adventofcode2024::error::{parse_lines, AocError};
+use adventofcode2024::sequence::{
+    bounded_deltas, decreasing, evaluate, increasing, Evaluation, PairRule, SequenceRule,
+};
+use adventofcode2024::solution::{self, Answer, Solution};
+use adventofcode2024::table::parse_fields;
+use itertools::Itertools;
+
+struct Day2;
+
+/// Removing levels can't leave a report shorter than this.
+const MIN_LEVELS: usize = 2;
+
+/// Why a report is unsafe, pointing at the (0-based) index of the level where the problem shows
+/// up. Levels are displayed 1-based.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Problem {
+    DirectionFlip { index: usize },
+    GapTooLarge { index: usize, gap: i32 },
+    EqualNeighbors { index: usize },
+    TooShort,
 }
 
-enum Direction {
-    Increasing,
-    Decreasing,
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::DirectionFlip { index } => {
+                write!(f, "level {} changes direction", index + 1)
+            }
+            Problem::GapTooLarge { index, gap } => {
+                write!(f, "level {} is {gap} away from the one before", index + 1)
+            }
+            Problem::EqualNeighbors { index } => {
+                write!(f, "level {} is equal to the one before", index + 1)
+            }
+            Problem::TooShort => write!(f, "fewer than two levels"),
+        }
+    }
 }
 
-struct Report {
-    pub nums: Vec<i32>,
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Safety {
+    /// Safe once the levels at these indices are removed (none if it's safe as is).
+    Safe { removed: Vec<usize> },
+    /// Unsafe even with removals; the problem is the first one in the report as given.
+    Unsafe(Problem),
 }
 
-struct RemovalIter<'a> {
-    src: &'a Report,
-    next_idx: usize,
+impl Safety {
+    fn is_safe(&self) -> bool {
+        matches!(self, Safety::Safe { .. })
+    }
 }
 
-impl Iterator for RemovalIter<'_> {
-    type Item = Report;
+#[derive(Clone, Copy, Debug)]
+struct SafetyRule {
+    max_step: i32,
+    max_removals: usize,
+}
+
+impl SafetyRule {
+    const STRICT: SafetyRule = SafetyRule {
+        max_step: 3,
+        max_removals: 0,
+    };
+    const DAMPENED: SafetyRule = SafetyRule {
+        max_removals: 1,
+        ..Self::STRICT
+    };
+
+    /// All increasing or all decreasing, by between 1 and `max_step` at a time.
+    fn levels(&self) -> impl SequenceRule<i32> {
+        let steps = || bounded_deltas(1, self.max_step.into());
+        increasing().and(steps()).or(decreasing().and(steps()))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.next_idx < self.src.nums.len() {
-            let mut next_vec = self.src.nums.clone();
-            next_vec.remove(self.next_idx);
-            let next_val = Report { nums: next_vec };
-            self.next_idx += 1;
-            Some(next_val)
+    /// What's wrong with the level at `index`, which the rules don't allow after the one before
+    /// it. If the step is neither flat nor too big, it must be in the wrong direction.
+    fn problem_at(&self, levels: &[i32], index: usize) -> Problem {
+        let gap = (levels[index] - levels[index - 1]).abs();
+        if gap == 0 {
+            Problem::EqualNeighbors { index }
+        } else if gap > self.max_step {
+            Problem::GapTooLarge { index, gap }
         } else {
-            None
+            Problem::DirectionFlip { index }
         }
     }
-}
-
-impl<'a> IntoIterator for &'a Report {
-    type Item = Report;
 
-    type IntoIter = RemovalIter<'a>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        RemovalIter {
-            src: self,
-            next_idx: 0,
+    fn check(&self, levels: &[i32]) -> Safety {
+        match evaluate(&self.levels(), levels, self.max_removals, MIN_LEVELS) {
+            Evaluation::Valid { removed } => Safety::Safe { removed },
+            Evaluation::Invalid { first_violation } => {
+                Safety::Unsafe(self.problem_at(levels, first_violation))
+            }
+            Evaluation::TooShort => Safety::Unsafe(Problem::TooShort),
         }
     }
 }
 
+struct Report {
+    pub nums: Vec<i32>,
+}
+
 impl Report {
     fn is_safe(&self) -> bool {
-        if self.nums.len() < 2 {
-            return false;
-        }
-        let direction = match self.nums.first().cmp(&self.nums.last()) {
-            Ordering::Less => Direction::Increasing,
-            Ordering::Greater => Direction::Decreasing,
-            Ordering::Equal => return false,
-        };
-        for pair in self.nums.windows(2) {
-            match direction {
-                Direction::Decreasing => {
-                    if pair[0] <= pair[1] || pair[0] - pair[1] > 3 {
-                        return false;
-                    }
-                }
-                Direction::Increasing => {
-                    if pair[0] >= pair[1] || pair[1] - pair[0] > 3 {
-                        return false;
-                    }
-                }
-            }
-        }
-        true
+        SafetyRule::STRICT.check(&self.nums).is_safe()
     }
 
     fn is_safe_with_problem_dampener(&self) -> bool {
-        self.is_safe() || self.into_iter().any(|it| it.is_safe())
+        SafetyRule::DAMPENED.check(&self.nums).is_safe()
     }
 }
 
-fn parse_inputs(inp: &str) -> Vec<Report> {
-    inp.lines()
-        .map(|l| Report {
-            nums: l
-                .split(" ")
-                .filter(|it| !it.is_empty())
-                .map(|it| it.parse::<i32>().unwrap())
-                .collect(),
+fn parse_inputs(inp: &str) -> Result<Vec<Report>, AocError> {
+    parse_lines(inp, |l| {
+        Ok(Report {
+            nums: parse_fields(l, "a level")?,
         })
-        .collect()
+    })
+}
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(parse_inputs(inp)?
+            .into_iter()
+            .filter(|it| it.is_safe())
+            .count()
+            .into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(parse_inputs(inp)?
+            .into_iter()
+            .filter(|it| it.is_safe_with_problem_dampener())
+            .count()
+            .into())
+    }
 }
 
-fn part1(inp: &str) -> usize {
-    parse_inputs(inp)
-        .into_iter()
-        .filter(|it| it.is_safe())
-        .count()
+/// Prints why each report that isn't safe as is fails, and what the dampener removes from it.
+fn explain(inp: &str) -> Result<(), AocError> {
+    for (idx, report) in parse_inputs(inp)?.into_iter().enumerate() {
+        let Safety::Unsafe(problem) = SafetyRule::STRICT.check(&report.nums) else {
+            continue;
+        };
+        let outcome = match SafetyRule::DAMPENED.check(&report.nums) {
+            Safety::Safe { removed } => format!(
+                "safe after removing level {}",
+                removed.iter().map(|it| (it + 1).to_string()).join(", ")
+            ),
+            Safety::Unsafe(..) => "unsafe".to_string(),
+        };
+        println!("report {}: {problem}; {outcome}", idx + 1);
+    }
+    Ok(())
 }
 
-fn part2(inp: &str) -> usize {
-    parse_inputs(inp)
-        .into_iter()
-        .filter(|it| it.is_safe_with_problem_dampener())
-        .count()
+fn main() -> ExitCode {
+    if std::env::args().any(|it| it == "--explain") {
+        return match Day2::input().and_then(|inp| explain(&inp)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    solution::run::<Day2>()
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day2::part1(EXAMPLE), Ok(Answer::Num(2)));
+        assert_eq!(Day2::part2(EXAMPLE), Ok(Answer::Num(4)));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let rule = SafetyRule::DAMPENED;
+        assert_eq!(
+            rule.check(&[1, 2, 7, 8, 9]),
+            Safety::Unsafe(Problem::GapTooLarge { index: 2, gap: 5 })
+        );
+        assert_eq!(
+            rule.check(&[1, 3, 2, 4, 5]),
+            Safety::Safe { removed: vec![2] }
+        );
+        assert_eq!(
+            rule.check(&[8, 6, 4, 4, 1]),
+            Safety::Safe { removed: vec![3] }
+        );
+        assert_eq!(
+            SafetyRule::STRICT.check(&[1, 3, 2, 4, 5]),
+            Safety::Unsafe(Problem::DirectionFlip { index: 2 })
+        );
+        assert_eq!(
+            SafetyRule::STRICT.check(&[7, 7, 8]),
+            Safety::Unsafe(Problem::EqualNeighbors { index: 1 })
+        );
+        // Dropping either level would leave just one.
+        assert_eq!(
+            rule.check(&[1, 9]),
+            Safety::Unsafe(Problem::GapTooLarge { index: 1, gap: 8 })
+        );
+        assert_eq!(rule.check(&[4]), Safety::Unsafe(Problem::TooShort));
+        // The first level is the odd one out, which comparing first and last can't see.
+        assert_eq!(
+            rule.check(&[5, 1, 2, 3, 4]),
+            Safety::Safe { removed: vec![0] }
+        );
+    }
 }
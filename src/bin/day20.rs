@@ -1,7 +1,15 @@
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Bounded, Coord, CoordDiff};
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day20;
+
+/// The fewest picoseconds a cheat has to save to count.
+const THRESHOLD: usize = 100;
 
 struct Maze<'a> {
     start: Coord,
@@ -94,10 +102,10 @@ fn solve(m: &Maze) -> Option<usize> {
     distances.get(&m.start).copied()
 }
 
-fn parse_inputs(inp: &str) -> Maze {
+fn parse_inputs(inp: &str) -> Result<Maze<'_>, AocError> {
     let mut size = CoordDiff { rows: 0, cols: 0 };
-    let mut start = Coord { row: -1, col: -1 };
-    let mut end = Coord { row: -1, col: -1 };
+    let mut start = None;
+    let mut end = None;
     let mut walls = HashSet::new();
     for (ri, r) in inp.lines().enumerate() {
         for (ci, c) in r.chars().enumerate() {
@@ -111,23 +119,38 @@ fn parse_inputs(inp: &str) -> Maze {
                 '#' => {
                     walls.insert(coord);
                 }
-                'E' => end = coord,
-                'S' => start = coord,
-                _ => (),
+                'E' | 'S' => {
+                    let slot = if c == 'S' { &mut start } else { &mut end };
+                    if slot.replace(coord).is_some() {
+                        return Err(AocError::invalid(format!("more than one {c}"))
+                            .at_line(ri + 1)
+                            .at_column(ci + 1));
+                    }
+                }
+                '.' => (),
+                other => {
+                    return Err(AocError::parse("one of #.SE", other)
+                        .at_line(ri + 1)
+                        .at_column(ci + 1))
+                }
             }
         }
     }
-    Maze {
+    Ok(Maze {
         size,
-        start,
-        end,
+        start: start.ok_or_else(|| AocError::invalid("no start (S) on the track"))?,
+        end: end.ok_or_else(|| AocError::invalid("no end (E) on the track"))?,
         walls: Cow::Owned(walls),
-    }
+    })
+}
+
+fn no_path() -> AocError {
+    AocError::invalid("there's no path from the start to the end")
 }
 
-fn part1(inp: &str, threshold: usize) -> usize {
-    let maze = parse_inputs(inp);
-    let base = solve(&maze).unwrap();
+fn part1(inp: &str, threshold: usize) -> Result<usize, AocError> {
+    let maze = parse_inputs(inp)?;
+    let base = solve(&maze).ok_or_else(no_path)?;
     let walls = maze.walls.iter().copied().collect::<Vec<_>>();
     let mut output = 0;
     for wi in 0..walls.len() {
@@ -144,30 +167,47 @@ fn part1(inp: &str, threshold: usize) -> usize {
             end: maze.end,
             walls: Cow::Owned(new_walls),
         };
-        let soln = solve(&new_maze).unwrap();
+        let soln = solve(&new_maze).ok_or_else(no_path)?;
         if base - soln >= threshold {
             output += 1;
         }
     }
-    output
+    Ok(output)
 }
 
-fn part1_alt(inp: &str, threshold: i64) -> usize {
-    let maze = parse_inputs(inp);
-    find_cheats(&maze, threshold, 2)
+fn part1_alt(inp: &str, threshold: i64) -> Result<usize, AocError> {
+    let maze = parse_inputs(inp)?;
+    Ok(find_cheats(&maze, threshold, 2))
 }
 
-fn part2(inp: &str, threshold: i64) -> usize {
-    let maze = parse_inputs(inp);
-    find_cheats(&maze, threshold, 20)
+fn part2(inp: &str, threshold: i64) -> Result<usize, AocError> {
+    let maze = parse_inputs(inp)?;
+    Ok(find_cheats(&maze, threshold, 20))
 }
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day20.txt").unwrap()
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp, THRESHOLD)?.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(part2(inp, THRESHOLD as i64)?.into())
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs(), 100));
-    println!("Part 1 (alt): {}", part1_alt(&inputs(), 100));
-    println!("Part 2: {}", part2(&inputs(), 100));
+fn main() -> ExitCode {
+    let status = solution::run::<Day20>();
+    // Part 1 again, counting cheats the same way as part 2.
+    if std::env::args().any(|it| it == "--alt") {
+        match Day20::input().and_then(|inp| part1_alt(&inp, THRESHOLD as i64)) {
+            Ok(count) => println!("Part 1 (alt): {count}"),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    status
 }
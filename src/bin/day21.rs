@@ -1,6 +1,12 @@
 use std::collections::HashMap;
+use std::process::ExitCode;
 
 use adventofcode2024::coords::{Bounded, Coord};
+use adventofcode2024::error::{parse_as, parse_lines, AocError};
+use adventofcode2024::solution::{self, Answer, Solution};
+use adventofcode2024::IntoWindowedIter;
+
+struct Day21;
 
 #[derive(Clone, Copy, Debug)]
 enum NumericKey {
@@ -17,9 +23,11 @@ enum NumericKey {
     A,
 }
 
-impl From<char> for NumericKey {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for NumericKey {
+    type Error = AocError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
             '1' => Self::N1,
             '2' => Self::N2,
             '3' => Self::N3,
@@ -31,8 +39,8 @@ impl From<char> for NumericKey {
             '9' => Self::N9,
             '0' => Self::N0,
             'A' => Self::A,
-            other => panic!("Invalid code {other}"),
-        }
+            other => return Err(AocError::parse("a numeric keypad key", other)),
+        })
     }
 }
 
@@ -90,16 +98,18 @@ impl Bounded for DirectionalKeypad {
         c.row >= 0 && c.col >= 0 && c.row < 2 && c.col < 3 && c != Coord { row: 0, col: 0 }
     }
 }
-impl From<char> for DirectionalKey {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for DirectionalKey {
+    type Error = AocError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
             '^' => Up,
             'v' => Down,
             '<' => Left,
             '>' => Right,
             'A' => A,
-            other => panic!("Invalid direction {other}"),
-        }
+            other => return Err(AocError::parse("a directional keypad key", other)),
+        })
     }
 }
 
@@ -115,11 +125,15 @@ impl std::fmt::Display for DirectionalKey {
     }
 }
 
-fn route_numeric(source: Coord, target: Coord, route: &mut Vec<DirectionalKey>) {
+fn route_numeric(
+    source: Coord,
+    target: Coord,
+    route: &mut Vec<DirectionalKey>,
+) -> Result<(), AocError> {
     let diff = target - source;
     if diff.is_zero() {
         route.push(DirectionalKey::A);
-        return;
+        return Ok(());
     }
     // We always try to continue in the former direction if possible.
     if diff.cols < 0
@@ -189,14 +203,20 @@ fn route_numeric(source: Coord, target: Coord, route: &mut Vec<DirectionalKey>)
             return route_numeric(source.r(), target, route);
         }
     }
-    unreachable!()
+    Err(AocError::invalid(format!(
+        "no route from {source:?} to {target:?}"
+    )))
 }
 
-fn route_directional(source: Coord, target: Coord, route: &mut Vec<DirectionalKey>) {
+fn route_directional(
+    source: Coord,
+    target: Coord,
+    route: &mut Vec<DirectionalKey>,
+) -> Result<(), AocError> {
     let diff = target - source;
     if diff.is_zero() {
         route.push(DirectionalKey::A);
-        return;
+        return Ok(());
     }
 
     // We always try to continue in the former direction if possible.
@@ -268,7 +288,9 @@ fn route_directional(source: Coord, target: Coord, route: &mut Vec<DirectionalKe
             return route_numeric(source.r(), target, route);
         }
     }
-    unreachable!()
+    Err(AocError::invalid(format!(
+        "no route from {source:?} to {target:?}"
+    )))
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -277,85 +299,81 @@ struct Pair {
     curr: DirectionalKey,
 }
 
-fn solve_at(depth: u32, pair: Pair, cache: &mut HashMap<(u32, Pair), u64>) -> u64 {
+fn solve_at(
+    depth: u32,
+    pair: Pair,
+    cache: &mut HashMap<(u32, Pair), u64>,
+) -> Result<u64, AocError> {
     if depth == 0 {
-        1
+        Ok(1)
     } else if let Some(e) = cache.get(&(depth, pair)) {
-        *e
+        Ok(*e)
     } else {
         let mut next_from_curr = vec![DirectionalKey::A];
-        route_directional(pair.prev.into(), pair.curr.into(), &mut next_from_curr);
+        route_directional(pair.prev.into(), pair.curr.into(), &mut next_from_curr)?;
         let result = next_from_curr
-            .windows(2)
-            .map(|window| {
-                solve_at(
-                    depth - 1,
-                    Pair {
-                        prev: window[0],
-                        curr: window[1],
-                    },
-                    cache,
-                )
-            })
-            .sum();
+            .into_iter()
+            .windowed::<2>()
+            .map(|[prev, curr]| solve_at(depth - 1, Pair { prev, curr }, cache))
+            .sum::<Result<u64, _>>()?;
         cache.insert((depth, pair), result);
-        result
+        Ok(result)
     }
 }
 
-fn solve_code(code: &str, n_middle_robots: u32) -> u64 {
+fn solve_code(code: &str, n_middle_robots: u32) -> Result<u64, AocError> {
     let mut next_round: Vec<DirectionalKey> = vec![DirectionalKey::A];
     let mut curr_pos = NumericKey::A;
-    for char in code.chars() {
-        let next_pos: NumericKey = char.into();
-        route_numeric(curr_pos.into(), next_pos.into(), &mut next_round);
-        //next_round.extend(route_numeric_old(curr_pos, next_pos));
+    for (idx, char) in code.chars().enumerate() {
+        let next_pos: NumericKey = char
+            .try_into()
+            .map_err(|e: AocError| e.at_column(idx + 1))?;
+        route_numeric(curr_pos.into(), next_pos.into(), &mut next_round)?;
         curr_pos = next_pos;
     }
 
     let mut cache = HashMap::new();
 
     next_round
-        .windows(2)
-        .map(|window| {
-            solve_at(
-                n_middle_robots,
-                Pair {
-                    prev: window[0],
-                    curr: window[1],
-                },
-                &mut cache,
-            )
-        })
+        .into_iter()
+        .windowed::<2>()
+        .map(|[prev, curr]| solve_at(n_middle_robots, Pair { prev, curr }, &mut cache))
         .sum()
 }
 
-fn code_complexity(code: &str, soln_len: u64) -> u64 {
-    let numeric_part: u64 = code.replace('A', "").parse().unwrap();
-    numeric_part * soln_len
+fn code_complexity(code: &str, soln_len: u64) -> Result<u64, AocError> {
+    let numeric_part: u64 = parse_as(&code.replace('A', ""), "a numeric code")?;
+    Ok(numeric_part * soln_len)
 }
 
-fn part1(codes: &[&str]) -> u64 {
-    codes
-        .iter()
-        .map(|c| (*c, solve_code(c, 2)))
-        .map(|(c, soln)| code_complexity(c, soln))
-        .sum()
-}
-
-fn part2(codes: &[&str]) -> u64 {
-    codes
-        .iter()
-        .map(|c| (*c, solve_code(c, 25)))
-        .map(|(c, soln)| code_complexity(c, soln))
-        .sum()
+fn total_complexity(inp: &str, n_middle_robots: u32) -> Result<u64, AocError> {
+    Ok(
+        parse_lines(inp, |c| code_complexity(c, solve_code(c, n_middle_robots)?))?
+            .into_iter()
+            .sum(),
+    )
 }
 
 const INPUT: &[&str] = &["539A", "964A", "803A", "149A", "789A"];
 
-fn main() {
-    println!("Part 1: {}", part1(INPUT));
-    println!("Part 2: {}", part2(INPUT));
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+
+    fn input() -> Result<String, AocError> {
+        Ok(INPUT.join("\n"))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(total_complexity(inp, 2)?.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(total_complexity(inp, 25)?.into())
+    }
+}
+
+fn main() -> ExitCode {
+    solution::run::<Day21>()
 }
 
 #[cfg(test)]
@@ -364,29 +382,38 @@ mod tests {
     #[test]
     fn test_code_solutions() {
         assert_eq!(
-            solve_code("029A", 2),
+            solve_code("029A", 2).unwrap(),
             "<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A".len() as u64
         );
         assert_eq!(
-            solve_code("980A", 2),
+            solve_code("980A", 2).unwrap(),
             "<v<A>>^AAAvA^A<vA<AA>>^AvAA<^A>A<v<A>A>^AAAvA<^A>A<vA>^A<A>A".len() as u64,
         );
         assert_eq!(
-            solve_code("179A", 2),
+            solve_code("179A", 2).unwrap(),
             "<v<A>>^A<vA<A>>^AAvAA<^A>A<v<A>>^AAvA^A<vA>^AA<A>A<v<A>A>^AAAvA<^A>A".len() as u64,
         );
         assert_eq!(
-            solve_code("456A", 2),
+            solve_code("456A", 2).unwrap(),
             "<v<A>>^AA<vA<A>>^AAvAA<^A>A<vA>^A<A>A<vA>^A<A>A<v<A>A>^AAvA<^A>A".len() as u64,
         );
         assert_eq!(
-            solve_code("379A", 2),
+            solve_code("379A", 2).unwrap(),
             "<v<A>>^AvA^A<vA<AA>>^AAvA<^A>AAvA^A<vA>^AA<A>A<v<A>A>^AAAvA<^A>A".len() as u64,
         );
     }
 
     #[test]
     fn test_p1() {
-        assert_eq!(part1(&["029A", "980A", "179A", "456A", "379A"]), 126384);
+        assert_eq!(
+            Day21::part1("029A\n980A\n179A\n456A\n379A"),
+            Ok(Answer::Num(126384))
+        );
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let err = Day21::part1("029A\n98xA").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(3)));
     }
 }
@@ -1,7 +1,13 @@
 use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
 
+use adventofcode2024::error::{parse_as, parse_lines, AocError};
+use adventofcode2024::solution::{self, Answer, Solution};
+use adventofcode2024::IntoWindowedIter;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
+struct Day22;
+
 fn mix(secret: i64, other: i64) -> i64 {
     secret ^ other
 }
@@ -25,15 +31,12 @@ fn step_n(mut secret: i64, n: u64) -> i64 {
     secret
 }
 
-fn gen_n(secret: i64, n: u64) -> Vec<i64> {
-    let mut output = vec![secret];
-    for _ in 0..n {
-        output.push(step(*output.last().unwrap()));
-    }
-    output
+/// The initial secret followed by the next `n`.
+fn gen_n(secret: i64, n: usize) -> impl Iterator<Item = i64> {
+    std::iter::successors(Some(secret), |it| Some(step(*it))).take(n + 1)
 }
 
-fn compute_seq_and_score(secrets: &[i64]) -> ([i64; 4], i64) {
+fn compute_seq_and_score(secrets: [i64; 5]) -> ([i64; 4], i64) {
     let score = secrets[4] % 10;
     let seq = [
         secrets[1] % 10 - secrets[0] % 10,
@@ -44,10 +47,10 @@ fn compute_seq_and_score(secrets: &[i64]) -> ([i64; 4], i64) {
     (seq, score)
 }
 
-fn compute_seqs(secret_seq: &[i64]) -> HashMap<[i64; 4], i64> {
+fn compute_seqs(secret_seq: impl Iterator<Item = i64>) -> HashMap<[i64; 4], i64> {
     let mut output = HashMap::new();
 
-    for window in secret_seq.windows(5) {
+    for window in secret_seq.windowed::<5>() {
         let (seq, score) = compute_seq_and_score(window);
         output.entry(seq).or_insert(score);
     }
@@ -55,43 +58,44 @@ fn compute_seqs(secret_seq: &[i64]) -> HashMap<[i64; 4], i64> {
     output
 }
 
-fn parse_inputs(inp: &str) -> Vec<i64> {
-    inp.lines().map(|it| it.parse().unwrap()).collect()
+fn parse_inputs(inp: &str) -> Result<Vec<i64>, AocError> {
+    parse_lines(inp, |l| parse_as(l, "a secret number"))
 }
 
-fn input() -> String {
-    std::fs::read_to_string("./inputs/day22.txt").unwrap()
-}
+impl Solution for Day22 {
+    const DAY: u32 = 22;
 
-fn part1(inp: &str) -> i64 {
-    parse_inputs(inp)
-        .into_iter()
-        .map(|it| step_n(it, 2000))
-        .sum()
-}
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(parse_inputs(inp)?
+            .into_iter()
+            .map(|it| step_n(it, 2000))
+            .sum::<i64>()
+            .into())
+    }
 
-fn part2(inp: &str) -> i64 {
-    let monkeys: Vec<_> = parse_inputs(inp)
-        .into_iter()
-        .map(|it| gen_n(it, 2000))
-        .map(|it| compute_seqs(&it))
-        .collect();
-    let all_seqs: HashSet<_> = monkeys.iter().flat_map(|it| it.keys().cloned()).collect();
-    all_seqs
-        .into_par_iter()
-        .map(|k| {
-            monkeys
-                .iter()
-                .map(|m| m.get(&k).copied().unwrap_or(0))
-                .sum()
-        })
-        .max()
-        .unwrap()
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        let monkeys: Vec<_> = parse_inputs(inp)?
+            .into_iter()
+            .map(|it| gen_n(it, 2000))
+            .map(compute_seqs)
+            .collect();
+        let all_seqs: HashSet<_> = monkeys.iter().flat_map(|it| it.keys().cloned()).collect();
+        all_seqs
+            .into_par_iter()
+            .map(|k| {
+                monkeys
+                    .iter()
+                    .map(|m| m.get(&k).copied().unwrap_or(0))
+                    .sum::<i64>()
+            })
+            .max()
+            .map(Answer::from)
+            .ok_or_else(|| AocError::invalid("no buyers in the input"))
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&input()));
-    println!("Part 2: {}", part2(&input()));
+fn main() -> ExitCode {
+    solution::run::<Day22>()
 }
 
 #[cfg(test)]
@@ -101,6 +105,6 @@ mod tests {
     #[test]
     fn test_p2() {
         let inp = "1\n2\n3\n2024";
-        assert_eq!(part2(inp), 23);
+        assert_eq!(Day22::part2(inp), Ok(Answer::Num(23)));
     }
 }
@@ -1,8 +1,10 @@
 use std::collections::HashSet;
+use std::process::ExitCode;
 
-fn input() -> String {
-    std::fs::read_to_string("./inputs/day23.txt").unwrap()
-}
+use adventofcode2024::error::{parse_lines, AocError};
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day23;
 
 struct NodeTriad<'a>(&'a str, &'a str, &'a str);
 
@@ -26,19 +28,21 @@ impl std::hash::Hash for NodeTriad<'_> {
     }
 }
 
-fn parse_input(inp: &str) -> HashSet<(&str, &str)> {
+fn parse_input(inp: &str) -> Result<HashSet<(&str, &str)>, AocError> {
     let mut output = HashSet::new();
-    inp.lines().for_each(|l| {
-        let parts = l.split("-").collect::<Vec<_>>();
-        output.insert((parts[0], parts[1]));
-        output.insert((parts[1], parts[0]));
-    });
+    for (a, b) in parse_lines(inp, |l| {
+        l.split_once("-")
+            .ok_or_else(|| AocError::parse("a connection like `kh-tc`", l))
+    })? {
+        output.insert((a, b));
+        output.insert((b, a));
+    }
 
-    output
+    Ok(output)
 }
 
-fn part1(inp: &str) -> usize {
-    let graph = parse_input(inp);
+fn part1(inp: &str) -> Result<usize, AocError> {
+    let graph = parse_input(inp)?;
     let mut triads: HashSet<NodeTriad> = HashSet::new();
 
     graph.iter().for_each(|(k0, k1)| {
@@ -58,10 +62,21 @@ fn part1(inp: &str) -> usize {
                 })
         }
     });
-    triads.len()
+    Ok(triads.len())
+}
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp)?.into())
+    }
+
+    fn part2(_inp: &str) -> Result<Answer, AocError> {
+        Err(AocError::unsolved("part 2 isn't implemented yet"))
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&input()));
-    todo!()
+fn main() -> ExitCode {
+    solution::run::<Day23>()
 }
@@ -1,33 +1,33 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::convert::Infallible;
+use std::collections::{HashMap, VecDeque};
 use std::hash::{Hash, Hasher};
+use std::process::ExitCode;
 use std::str::FromStr;
 
+use adventofcode2024::error::{parse_as, parse_lines, AocError};
 use adventofcode2024::parsers::{Parser, Parsers};
-use indicatif::ParallelProgressIterator;
-use itertools::Itertools;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use adventofcode2024::solution::{self, read_input, Answer, Solution};
+
+struct Day24;
 
 struct Wire {
     name: String,
     value: bool,
 }
 
-fn parse_wires(inp: &str) -> Vec<Wire> {
-    inp.lines()
-        .map(|l| {
-            let parts = l.split(": ").collect::<Vec<_>>();
-            let value: u8 = parts[1].parse().unwrap();
-            Wire {
-                name: parts[0].to_string(),
-                value: match value {
-                    0 => false,
-                    1 => true,
-                    other => panic!("Unexpected wire value {other}"),
-                },
-            }
+fn parse_wires(inp: &str) -> Result<Vec<Wire>, AocError> {
+    parse_lines(inp, |l| {
+        let (name, value) = l
+            .split_once(": ")
+            .ok_or_else(|| AocError::parse("a wire like `x00: 1`", l))?;
+        Ok(Wire {
+            name: name.to_string(),
+            value: match parse_as::<u8>(value, "a wire value")? {
+                0 => false,
+                1 => true,
+                other => return Err(AocError::parse("a wire value of 0 or 1", other.to_string())),
+            },
         })
-        .collect()
+    })
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -48,14 +48,14 @@ impl Op {
 }
 
 impl FromStr for Op {
-    type Err = Infallible;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "AND" => Op::And,
             "OR" => Op::Or,
             "XOR" => Op::Xor,
-            other => panic!("Unexpected operator {other}"),
+            other => return Err(AocError::parse("AND, OR or XOR", other)),
         })
     }
 }
@@ -68,50 +68,48 @@ struct Gate {
     output: String,
 }
 
-fn parse_gates(inp: &str) -> Vec<Gate> {
+fn parse_gates(inp: &str) -> Result<Vec<Gate>, AocError> {
     let p = Parsers::r("[a-z0-9]{3}")
         .followed_by(" ")
-        .and(Parsers::r("AND|XOR|OR"))
+        .and(Parsers::r("[A-Z]+"))
         .followed_by(" ")
         .and(Parsers::r("[a-z0-9]{3}"))
         .followed_by(" -> ")
         .and(Parsers::r("[a-z0-9]{3}"));
-    inp.lines()
-        .map(|l| p.apply(l).unwrap_value())
-        .map(|(((lhs, op), rhs), output)| Gate {
+    parse_lines(inp, |l| {
+        let (((lhs, op), rhs), output) = p
+            .apply(l)
+            .value_or_error("a gate like `x00 AND y00 -> z00`", l)?;
+        Ok(Gate {
             lhs,
             rhs,
-            op: op.parse().unwrap(),
+            op: op.parse()?,
             output,
         })
-        .collect()
-}
-
-fn parse_inputs(inp: &str, gates: &str) -> (Vec<Wire>, Vec<Gate>) {
-    (parse_wires(inp), parse_gates(gates))
+    })
 }
 
-fn wire_input() -> String {
-    std::fs::read_to_string("./inputs/day24_input_wires.txt").unwrap()
+fn parse_inputs(inp: &str) -> Result<(Vec<Wire>, Vec<Gate>), AocError> {
+    let (wires, gates) = inp
+        .split_once("\n\n")
+        .ok_or_else(|| AocError::invalid("expected wires and gates separated by a blank line"))?;
+    let gates = parse_gates(gates).map_err(|e| e.offset_lines(wires.lines().count() + 1))?;
+    Ok((parse_wires(wires)?, gates))
 }
 
-fn gate_input() -> String {
-    std::fs::read_to_string("./inputs/day24_gates.txt").unwrap()
-}
-
-fn calculate_score(values: &HashMap<String, bool>, scoring_char: char) -> u64 {
+fn calculate_score(values: &HashMap<String, bool>, scoring_char: char) -> Result<u64, AocError> {
     let mut result: u64 = 0;
 
     for (k, v) in values {
-        if k.starts_with(scoring_char) {
-            if *v {
-                let num: u64 = k[1..].parse().unwrap();
-                result |= 1 << num;
-            }
+        if k.starts_with(scoring_char) && *v {
+            let num: u32 = parse_as(&k[1..], "a numbered wire")?;
+            result |= 1u64.checked_shl(num).ok_or_else(|| {
+                AocError::invalid(format!("{k} is past the 64 bits an output can have"))
+            })?;
         }
     }
 
-    result
+    Ok(result)
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -154,16 +152,11 @@ fn propagate_circuit(
     Ok(values)
 }
 
-fn part1(wires: &str, gates: &str) -> u64 {
-    let (wires, gates) = parse_inputs(wires, gates);
-    let values = propagate_circuit(&wires, &gates).unwrap();
-    calculate_score(&values, 'z')
-}
-
-fn part2(wires: &str, gates: &str) -> String {
-    let (wires, gates) = parse_inputs(wires, gates);
-    let values = propagate_circuit(&wires, &gates).unwrap();
-    // This is the base for a .dot graphvis file I'll use to accompany the python script.
+// This is the base for a .dot graphvis file I'll use to accompany the python script.
+fn print_graph(inp: &str) -> Result<(), AocError> {
+    let (wires, gates) = parse_inputs(inp)?;
+    let values = propagate_circuit(&wires, &gates)
+        .map_err(|_| AocError::invalid("the circuit has a cycle"))?;
     for node in values.keys() {
         println!("{};", node);
     }
@@ -171,10 +164,57 @@ fn part2(wires: &str, gates: &str) -> String {
         println!("{} -> {};", gate.lhs, gate.output);
         println!("{} -> {};", gate.rhs, gate.output);
     }
-    todo!()
+    Ok(())
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&wire_input(), &gate_input()));
-    println!("Part 2: {}", part2(&wire_input(), &gate_input()));
+impl Solution for Day24 {
+    const DAY: u32 = 24;
+
+    fn input() -> Result<String, AocError> {
+        let wires = read_input("day24_input_wires.txt")?;
+        let gates = read_input("day24_gates.txt")?;
+        Ok(format!("{}\n\n{}", wires.trim_end(), gates))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        let (wires, gates) = parse_inputs(inp)?;
+        let values = propagate_circuit(&wires, &gates)
+            .map_err(|_| AocError::invalid("the circuit has a cycle"))?;
+        Ok(calculate_score(&values, 'z')?.into())
+    }
+
+    fn part2(_inp: &str) -> Result<Answer, AocError> {
+        Err(AocError::unsolved(
+            "found by hand with day24.py and the graph from `day24 --dot`",
+        ))
+    }
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|it| it == "--dot") {
+        return match Day24::input().and_then(|inp| print_graph(&inp)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    solution::run::<Day24>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_score() {
+        let values = |wires: &[(&str, bool)]| -> HashMap<String, bool> {
+            wires.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+        };
+        let wires = values(&[("z00", true), ("z02", true), ("z01", false), ("x05", true)]);
+        assert_eq!(calculate_score(&wires, 'z'), Ok(5));
+        assert_eq!(calculate_score(&values(&[("z63", true)]), 'z'), Ok(1 << 63));
+        assert!(calculate_score(&values(&[("z64", true)]), 'z').is_err());
+    }
 }
@@ -1,3 +1,10 @@
+use std::process::ExitCode;
+
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day25;
+
 #[derive(Clone, Debug, Default)]
 struct Key {
     heights: [u8; 5],
@@ -34,31 +41,40 @@ impl Item {
     }
 }
 
-fn parse_inputs(inp: &str) -> (Vec<Key>, Vec<Lock>) {
+fn parse_inputs(inp: &str) -> Result<(Vec<Key>, Vec<Lock>), AocError> {
     let mut keys = vec![];
     let mut locks = vec![];
 
     let mut curr_item: Option<Item> = None;
 
-    for line in inp.lines() {
+    for (li, line) in inp.lines().enumerate() {
         if line.is_empty() {
             match curr_item.take() {
                 Some(Item::K(key)) => keys.push(key),
                 Some(Item::L(lock)) => locks.push(lock),
-                None => panic!("Should not have two empty lines in a row"),
+                None => return Err(AocError::invalid("two empty lines in a row").at_line(li + 1)),
             }
         } else {
-            if curr_item.is_none() {
+            // Locks have their top row filled in, keys have it empty.
+            let item = curr_item.get_or_insert_with(|| {
                 if line.contains('#') {
-                    curr_item = Some(Item::L(Default::default()));
+                    Item::L(Default::default())
                 } else {
-                    assert!(line.contains('.'));
-                    curr_item = Some(Item::K(Default::default()));
+                    Item::K(Default::default())
                 }
-            }
+            });
             for (ci, c) in line.chars().enumerate() {
-                if c == '#' {
-                    curr_item.as_mut().unwrap().heights()[ci] += 1;
+                let at = |e: AocError| e.at_line(li + 1).at_column(ci + 1);
+                match c {
+                    '#' => {
+                        let height = item
+                            .heights()
+                            .get_mut(ci)
+                            .ok_or_else(|| at(AocError::invalid("more than 5 columns")))?;
+                        *height += 1;
+                    }
+                    '.' => {}
+                    other => return Err(at(AocError::parse("# or .", other))),
                 }
             }
         }
@@ -69,15 +85,11 @@ fn parse_inputs(inp: &str) -> (Vec<Key>, Vec<Lock>) {
             Item::L(lock) => locks.push(lock),
         }
     }
-    (keys, locks)
-}
-
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day25.txt").unwrap()
+    Ok((keys, locks))
 }
 
-fn part1(inp: &str) -> u64 {
-    let (keys, locks) = parse_inputs(inp);
+fn part1(inp: &str) -> Result<u64, AocError> {
+    let (keys, locks) = parse_inputs(inp)?;
     let mut total_pairs: u64 = 0;
     for l in locks {
         for k in keys.iter() {
@@ -86,9 +98,22 @@ fn part1(inp: &str) -> u64 {
             }
         }
     }
-    total_pairs
+    Ok(total_pairs)
+}
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(part1(inp)?.into())
+    }
+
+    /// Day 25 only has one puzzle.
+    fn part2(_inp: &str) -> Result<Answer, AocError> {
+        Err(AocError::unsolved("there is no part 2"))
+    }
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
+fn main() -> ExitCode {
+    solution::run::<Day25>()
 }
@@ -1,58 +1,249 @@
-use adventofcode2024::parsers::{Either, ParseResult, Parser, Parsers};
-
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day3.txt").unwrap()
-}
-
-fn mul() -> impl Parser<Target = (u64, u64)> {
-    Parsers::lit("mul(")
-        .then(Parsers::num())
-        .followed_by(",")
-        .and(Parsers::num().followed_by(")"))
-}
-
-fn part1(inp: &str) -> u64 {
-    match mul().apply(inp) {
-        ParseResult::Success {
-            value: (a, b),
-            rest,
-        } => a * b + (if rest.is_empty() { 0 } else { part1(rest) }),
-        ParseResult::Failure => {
-            if !inp.is_empty() {
-                part1(&inp[1..])
-            } else {
-                0
+use std::process::ExitCode;
+
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+use itertools::Itertools;
+
+struct Day3;
+
+/// The state the instructions act on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Machine {
+    enabled: bool,
+    total: u64,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Machine {
+            enabled: true,
+            total: 0,
+        }
+    }
+}
+
+/// An instruction written as `name(op,op,...)` with exactly `arity` operands.
+struct InstructionSpec {
+    name: &'static str,
+    arity: usize,
+    /// Gated instructions are ignored while the machine is disabled.
+    gated: bool,
+    execute: fn(&mut Machine, &[u64]),
+}
+
+const MUL: InstructionSpec = InstructionSpec {
+    name: "mul",
+    arity: 2,
+    gated: true,
+    execute: |m, ops| m.total += ops[0] * ops[1],
+};
+const DO: InstructionSpec = InstructionSpec {
+    name: "do",
+    arity: 0,
+    gated: false,
+    execute: |m, _| m.enabled = true,
+};
+const DONT: InstructionSpec = InstructionSpec {
+    name: "don't",
+    arity: 0,
+    gated: false,
+    execute: |m, _| m.enabled = false,
+};
+
+/// A well-formed instruction found in the corrupted memory, at byte `offset`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Token {
+    offset: usize,
+    instruction: &'static str,
+    operands: Vec<u64>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Step {
+    token: Token,
+    executed: bool,
+}
+
+struct InstructionSet {
+    specs: Vec<InstructionSpec>,
+    /// Operands have between 1 and this many digits.
+    max_digits: usize,
+}
+
+impl InstructionSet {
+    fn new(specs: Vec<InstructionSpec>) -> Self {
+        InstructionSet {
+            specs,
+            max_digits: 3,
+        }
+    }
+
+    /// Parses `(op,op,...)` with `arity` operands from the start of `s`, returning the operands
+    /// and the length of the text they took up.
+    fn operands(&self, s: &str, arity: usize) -> Option<(Vec<u64>, usize)> {
+        let mut rest = s.strip_prefix('(')?;
+        let mut operands = Vec::with_capacity(arity);
+        for idx in 0..arity {
+            if idx > 0 {
+                rest = rest.strip_prefix(',')?;
+            }
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            if !(1..=self.max_digits).contains(&digits) {
+                return None;
             }
+            operands.push(rest[..digits].parse().ok()?);
+            rest = &rest[digits..];
         }
+        rest = rest.strip_prefix(')')?;
+        Some((operands, s.len() - rest.len()))
+    }
+
+    fn token_at(&self, s: &str, offset: usize) -> Option<(&InstructionSpec, Token, usize)> {
+        self.specs.iter().find_map(|spec| {
+            let args = s.strip_prefix(spec.name)?;
+            let (operands, len) = self.operands(args, spec.arity)?;
+            let token = Token {
+                offset,
+                instruction: spec.name,
+                operands,
+            };
+            Some((spec, token, spec.name.len() + len))
+        })
+    }
+
+    /// Every well-formed instruction in `inp`, skipping over whatever's between them.
+    fn tokenize<'a>(
+        &'a self,
+        inp: &'a str,
+    ) -> impl Iterator<Item = (&'a InstructionSpec, Token)> + 'a {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            while offset < inp.len() {
+                if let Some((spec, token, len)) = self.token_at(&inp[offset..], offset) {
+                    offset += len;
+                    return Some((spec, token));
+                }
+                offset += inp[offset..].chars().next().map_or(1, char::len_utf8);
+            }
+            None
+        })
+    }
+
+    fn run(&self, inp: &str) -> (Machine, Vec<Step>) {
+        let mut machine = Machine::default();
+        let trace = self
+            .tokenize(inp)
+            .map(|(spec, token)| {
+                let executed = machine.enabled || !spec.gated;
+                if executed {
+                    (spec.execute)(&mut machine, &token.operands);
+                }
+                Step { token, executed }
+            })
+            .collect();
+        (machine, trace)
     }
 }
 
-#[derive(Clone, Copy)]
-enum ParseState {
-    Enabled,
-    Disabled,
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(InstructionSet::new(vec![MUL]).run(inp).0.total.into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(InstructionSet::new(vec![MUL, DO, DONT])
+            .run(inp)
+            .0
+            .total
+            .into())
+    }
 }
 
-fn part2(inp: &str, state: ParseState) -> u64 {
-    if inp.is_empty() {
-        return 0;
+fn print_trace(inp: &str) {
+    let (machine, trace) = InstructionSet::new(vec![MUL, DO, DONT]).run(inp);
+    for Step { token, executed } in trace {
+        println!(
+            "{:>6}: {}({}) {}",
+            token.offset,
+            token.instruction,
+            token.operands.iter().join(","),
+            if executed { "executed" } else { "ignored" }
+        );
     }
-    match state {
-        ParseState::Disabled => match Parsers::lit("do()").apply(inp) {
-            ParseResult::Failure => part2(&inp[1..], state),
-            ParseResult::Success { rest, .. } => part2(rest, ParseState::Enabled),
-        },
-        ParseState::Enabled => match Parsers::lit("don't()").or(mul()).apply(inp) {
-            ParseResult::Failure => part2(&inp[1..], state),
-            ParseResult::Success { value, rest } => match value {
-                Either::Left(..) => part2(rest, ParseState::Disabled),
-                Either::Right((a, b)) => a * b + part2(rest, state),
-            },
-        },
+    println!("total: {}", machine.total);
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|it| it == "--trace") {
+        return match Day3::input() {
+            Ok(inp) => {
+                print_trace(&inp);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
     }
+    solution::run::<Day3>()
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs(), ParseState::Enabled));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p1() {
+        let inp = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        assert_eq!(Day3::part1(inp), Ok(Answer::Num(161)));
+    }
+
+    #[test]
+    fn test_p2() {
+        let inp = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(Day3::part2(inp), Ok(Answer::Num(48)));
+        let (_, trace) = InstructionSet::new(vec![MUL, DO, DONT]).run(inp);
+        let executed: Vec<_> = trace
+            .iter()
+            .map(|it| (it.token.instruction, it.executed))
+            .collect();
+        assert_eq!(
+            executed,
+            vec![
+                ("mul", true),
+                ("don't", true),
+                ("mul", false),
+                ("mul", false),
+                ("do", true),
+                ("mul", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operand_limits() {
+        let set = InstructionSet::new(vec![MUL, DO]);
+        let tokens: Vec<_> = set
+            .tokenize("mul(1234,5)mul(123,45)mul(,1)do(1)do()")
+            .map(|(_, token)| token)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    offset: 11,
+                    instruction: "mul",
+                    operands: vec![123, 45]
+                },
+                Token {
+                    offset: 34,
+                    instruction: "do",
+                    operands: vec![]
+                }
+            ]
+        );
+    }
 }
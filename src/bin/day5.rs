@@ -1,30 +1,144 @@
-use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-
-pub type Rules = HashMap<u32, HashSet<u32>>;
-
-pub fn parse_rules(inp: &str) -> Rules {
-    let mut output: Rules = HashMap::new();
-    inp.lines()
-        .map(|it| {
-            it.split("|")
-                .map(|it| it.parse().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .for_each(|it| {
-            output
-                .entry(it[1])
-                .and_modify(|s| {
-                    s.insert(it[0]);
-                })
-                .or_insert_with(|| HashSet::from([it[0]]));
-        });
-
-    output
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::process::ExitCode;
+
+use adventofcode2024::error::{parse_as, parse_lines, AocError};
+use adventofcode2024::solution::{self, read_input, Answer, Solution};
+use itertools::Itertools;
+
+struct Day5;
+
+/// `before` has to be printed at some point before `after`, if both are in an update.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Rule {
+    before: u32,
+    after: u32,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.before, self.after)
+    }
 }
 
-fn rules() -> Rules {
-    parse_rules(&std::fs::read_to_string("./inputs/day5_rules.txt").unwrap())
+/// The ordering rules as a graph with an edge from each page to the pages that must follow it.
+struct RulesGraph {
+    successors: HashMap<u32, HashSet<u32>>,
+}
+
+impl RulesGraph {
+    fn parse(inp: &str) -> Result<RulesGraph, AocError> {
+        let rules = parse_lines(inp, |l| {
+            let (before, after) = l
+                .split_once('|')
+                .ok_or_else(|| AocError::parse("a rule like 47|53", l))?;
+            Ok(Rule {
+                before: parse_as(before, "a page number")?,
+                after: parse_as(after, "a page number")?,
+            })
+        })?;
+        let mut successors: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for Rule { before, after } in rules {
+            successors.entry(before).or_default().insert(after);
+        }
+        Ok(RulesGraph { successors })
+    }
+
+    fn must_follow(&self, page: u32) -> impl Iterator<Item = u32> + '_ {
+        self.successors.get(&page).into_iter().flatten().copied()
+    }
+
+    /// The first rule the update breaks, found in one pass by checking each page against the
+    /// pages already printed.
+    fn violation(&self, update: &[u32]) -> Option<Rule> {
+        let mut printed: HashMap<u32, usize> = HashMap::new();
+        for (idx, page) in update.iter().copied().enumerate() {
+            let earliest = self
+                .must_follow(page)
+                .filter_map(|after| printed.get(&after).map(|pos| (*pos, after)))
+                .min();
+            if let Some((_, after)) = earliest {
+                return Some(Rule {
+                    before: page,
+                    after,
+                });
+            }
+            printed.insert(page, idx);
+        }
+        None
+    }
+
+    /// Orders `pages` by the rules between them with Kahn's algorithm. Pages the rules don't
+    /// order relative to each other keep their original order.
+    fn sort(&self, pages: &[u32]) -> Result<Vec<u32>, Cycle> {
+        let position: HashMap<u32, usize> =
+            pages.iter().enumerate().map(|(i, p)| (*p, i)).collect();
+        let mut in_degree = vec![0; pages.len()];
+        for page in pages {
+            for after in self.must_follow(*page) {
+                if let Some(idx) = position.get(&after) {
+                    in_degree[*idx] += 1;
+                }
+            }
+        }
+        let mut ready: BTreeSet<usize> = (0..pages.len()).filter(|i| in_degree[*i] == 0).collect();
+        let mut sorted = Vec::with_capacity(pages.len());
+        while let Some(idx) = ready.pop_first() {
+            sorted.push(pages[idx]);
+            for after in self.must_follow(pages[idx]) {
+                if let Some(next) = position.get(&after) {
+                    in_degree[*next] -= 1;
+                    if in_degree[*next] == 0 {
+                        ready.insert(*next);
+                    }
+                }
+            }
+        }
+        if sorted.len() == pages.len() {
+            Ok(sorted)
+        } else {
+            let remaining: HashSet<u32> = (0..pages.len())
+                .filter(|i| in_degree[*i] > 0)
+                .map(|i| pages[i])
+                .collect();
+            Err(self.find_cycle(&remaining))
+        }
+    }
+
+    /// Every page left over from a stalled sort still has a predecessor among the others, so
+    /// following those backwards from any of them must eventually repeat a page.
+    fn find_cycle(&self, remaining: &HashSet<u32>) -> Cycle {
+        let predecessor = |page: u32| {
+            remaining
+                .iter()
+                .copied()
+                .filter(|p| self.must_follow(*p).any(|it| it == page))
+                .min()
+                .expect("pages left after sorting have a predecessor")
+        };
+        let mut path = vec![*remaining.iter().min().expect("a cycle isn't empty")];
+        loop {
+            let prev = predecessor(*path.last().unwrap());
+            if let Some(start) = path.iter().position(|it| *it == prev) {
+                let mut cycle = path.split_off(start);
+                cycle.reverse();
+                let first = cycle.iter().position_min().unwrap();
+                cycle.rotate_left(first);
+                return Cycle(cycle);
+            }
+            path.push(prev);
+        }
+    }
+}
+
+/// Pages whose rules require each to come before the next, and the last before the first.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Cycle(Vec<u32>);
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.0.iter().join(" -> "), self.0[0])
+    }
 }
 
 struct Update(Vec<u32>);
@@ -33,52 +147,132 @@ impl Update {
     pub fn mid(&self) -> u32 {
         self.0[self.0.len() / 2]
     }
-
-    pub fn sorted(&self, rules: &Rules) -> Update {
-        let mut new_values = self.0.clone();
-        new_values.sort_by(|a, b| {
-            if rules.get(b).map(|it| it.contains(a)).unwrap_or_default() {
-                Ordering::Less
-            } else if rules.get(a).map(|it| it.contains(b)).unwrap_or_default() {
-                Ordering::Greater
-            } else {
-                Ordering::Equal
-            }
-        });
-        Update(new_values)
-    }
 }
 
-fn parse_updates(inp: &str) -> Vec<Update> {
-    inp.lines()
-        .filter(|it| !it.is_empty())
-        .map(|l| l.split(",").map(|it| it.parse().unwrap()))
-        .map(|it| Update(it.collect()))
-        .collect()
+fn parse_updates(inp: &str) -> Result<Vec<Update>, AocError> {
+    let updates = parse_lines(inp, |l| {
+        l.split(',')
+            .map(|it| parse_as(it, "a page number"))
+            .collect::<Result<_, _>>()
+            .map(Update)
+    })?;
+    Ok(updates.into_iter().filter(|it| !it.0.is_empty()).collect())
 }
 
-fn updates() -> Vec<Update> {
-    parse_updates(&std::fs::read_to_string("./inputs/day5_updates.txt").unwrap())
+fn parse_inputs(inp: &str) -> Result<(RulesGraph, Vec<Update>), AocError> {
+    let (rules, updates) = inp
+        .split_once("\n\n")
+        .ok_or_else(|| AocError::invalid("expected rules and updates separated by a blank line"))?;
+    let updates = parse_updates(updates).map_err(|e| e.offset_lines(rules.lines().count() + 1))?;
+    Ok((RulesGraph::parse(rules)?, updates))
 }
 
-fn part1() -> u32 {
-    updates()
-        .into_iter()
-        .filter(|it| it.sorted(&rules()).0 == it.0)
-        .map(|it| it.mid())
-        .sum()
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    fn input() -> Result<String, AocError> {
+        let rules = read_input("day5_rules.txt")?;
+        let updates = read_input("day5_updates.txt")?;
+        Ok(format!("{}\n\n{}", rules.trim_end(), updates))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        let (rules, updates) = parse_inputs(inp)?;
+        Ok(updates
+            .iter()
+            .filter(|it| rules.violation(&it.0).is_none())
+            .map(|it| it.mid())
+            .sum::<u32>()
+            .into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        let (rules, updates) = parse_inputs(inp)?;
+        let mut total = 0;
+        for update in updates.iter() {
+            if rules.violation(&update.0).is_some() {
+                let sorted = rules.sort(&update.0).map_err(|cycle| {
+                    AocError::invalid(format!(
+                        "the rules for update {:?} form a cycle: {cycle}",
+                        update.0
+                    ))
+                })?;
+                total += Update(sorted).mid();
+            }
+        }
+        Ok(total.into())
+    }
 }
 
-fn part2() -> u32 {
-    let r = rules();
-    updates()
-        .into_iter()
-        .filter(|it| it.sorted(&r).0 != it.0)
-        .map(|it| it.sorted(&r).mid())
-        .sum()
+fn main() -> ExitCode {
+    solution::run::<Day5>()
 }
 
-fn main() {
-    println!("Part 1: {}", part1());
-    println!("Part 2: {}", part2());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day5::part1(EXAMPLE), Ok(Answer::Num(143)));
+        assert_eq!(Day5::part2(EXAMPLE), Ok(Answer::Num(123)));
+    }
+
+    #[test]
+    fn test_violation() {
+        let (rules, _) = parse_inputs(EXAMPLE).unwrap();
+        assert_eq!(rules.violation(&[75, 47, 61, 53, 29]), None);
+        assert_eq!(
+            rules.violation(&[75, 97, 47, 61, 53]),
+            Some(Rule {
+                before: 97,
+                after: 75
+            })
+        );
+        assert_eq!(
+            rules.sort(&[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let rules = RulesGraph::parse("1|2\n2|3\n3|1\n3|4").unwrap();
+        assert_eq!(rules.sort(&[4, 3, 2]), Ok(vec![2, 3, 4]));
+        let cycle = rules.sort(&[4, 3, 2, 1]).unwrap_err();
+        assert_eq!(cycle, Cycle(vec![1, 2, 3]));
+        assert_eq!(cycle.to_string(), "1 -> 2 -> 3 -> 1");
+        let err = Day5::part2("1|2\n2|1\n\n2,1").unwrap_err();
+        assert!(err.to_string().contains("cycle: 1 -> 2 -> 1"), "{err}");
+    }
 }
@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+use std::process::ExitCode;
+
+use adventofcode2024::coords::{Bounded, Coord, CoordDiff};
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+struct Day6;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn turn_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    fn step(&self, c: Coord) -> Coord {
+        match self {
+            Direction::Up => c.u(),
+            Direction::Down => c.d(),
+            Direction::Left => c.l(),
+            Direction::Right => c.r(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+struct Guard {
+    pos: Coord,
+    dir: Direction,
+}
+
+struct Lab {
+    obstructions: HashSet<Coord>,
+    size: CoordDiff,
+    guard: Guard,
+}
+
+impl Bounded for Lab {
+    fn in_bounds(&self, c: Coord) -> bool {
+        c.row >= 0 && c.col >= 0 && c.row < self.size.rows && c.col < self.size.cols
+    }
+}
+
+enum Patrol {
+    /// The guard walked off the map after visiting these positions.
+    Exits(HashSet<Coord>),
+    Loops,
+}
+
+impl Lab {
+    /// Where the guard is after one step: forwards if that's clear, otherwise turned right.
+    /// `extra` is an additional obstruction to take into account.
+    fn next(&self, guard: Guard, extra: Option<Coord>) -> Option<Guard> {
+        let ahead = guard.dir.step(guard.pos);
+        if !self.in_bounds(ahead) {
+            None
+        } else if self.obstructions.contains(&ahead) || extra == Some(ahead) {
+            Some(Guard {
+                pos: guard.pos,
+                dir: guard.dir.turn_right(),
+            })
+        } else {
+            Some(Guard {
+                pos: ahead,
+                dir: guard.dir,
+            })
+        }
+    }
+
+    /// Follows the guard, calling `visit` with each position, until they leave (`false`) or
+    /// until they're somewhere they've already been facing the same way, which means they'll go
+    /// round forever (`true`). A loop has to pass through the same turn twice, so turns are the
+    /// only states worth remembering.
+    fn walk(&self, extra: Option<Coord>, mut visit: impl FnMut(Coord)) -> bool {
+        let mut turns = HashSet::new();
+        let mut guard = Some(self.guard);
+        while let Some(g) = guard {
+            visit(g.pos);
+            guard = self.next(g, extra);
+            if guard.is_some_and(|it| it.dir != g.dir) && !turns.insert(g) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn patrol(&self) -> Patrol {
+        let mut visited = HashSet::new();
+        if self.walk(None, |pos| {
+            visited.insert(pos);
+        }) {
+            Patrol::Loops
+        } else {
+            Patrol::Exits(visited)
+        }
+    }
+}
+
+fn parse_inputs(inp: &str) -> Result<Lab, AocError> {
+    let mut obstructions = HashSet::new();
+    let mut guard = None;
+    let mut size = CoordDiff { rows: 0, cols: 0 };
+    for (row, line) in inp.lines().enumerate() {
+        size.rows = row as i64 + 1;
+        size.cols = size.cols.max(line.len() as i64);
+        for (col, ch) in line.chars().enumerate() {
+            let pos = Coord::from_xy(col, row);
+            let dir = match ch {
+                '#' => {
+                    obstructions.insert(pos);
+                    continue;
+                }
+                '.' => continue,
+                '^' => Direction::Up,
+                'v' => Direction::Down,
+                '<' => Direction::Left,
+                '>' => Direction::Right,
+                other => {
+                    return Err(AocError::parse("one of .#^v<>", other)
+                        .at_line(row + 1)
+                        .at_column(col + 1))
+                }
+            };
+            if guard.replace(Guard { pos, dir }).is_some() {
+                return Err(AocError::invalid("more than one guard")
+                    .at_line(row + 1)
+                    .at_column(col + 1));
+            }
+        }
+    }
+    Ok(Lab {
+        obstructions,
+        size,
+        guard: guard.ok_or_else(|| AocError::invalid("no guard on the map"))?,
+    })
+}
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        match parse_inputs(inp)?.patrol() {
+            Patrol::Exits(visited) => Ok(visited.len().into()),
+            Patrol::Loops => Err(AocError::invalid("the guard never leaves")),
+        }
+    }
+
+    /// A new obstruction can only change the guard's route if it's somewhere on it, so those are
+    /// the only candidates worth simulating.
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        let lab = parse_inputs(inp)?;
+        let Patrol::Exits(visited) = lab.patrol() else {
+            return Err(AocError::invalid("the guard never leaves"));
+        };
+        Ok(visited
+            .into_par_iter()
+            .filter(|c| *c != lab.guard.pos)
+            .filter(|c| lab.walk(Some(*c), |_| ()))
+            .count()
+            .into())
+    }
+}
+
+fn main() -> ExitCode {
+    solution::run::<Day6>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day6::part1(EXAMPLE), Ok(Answer::Num(41)));
+        assert_eq!(Day6::part2(EXAMPLE), Ok(Answer::Num(6)));
+    }
+
+    #[test]
+    fn test_input() {
+        let inp = Day6::input().unwrap();
+        assert_eq!(Day6::part1(&inp), Ok(Answer::Num(5153)));
+        assert_eq!(Day6::part2(&inp), Ok(Answer::Num(1711)));
+    }
+
+    #[test]
+    fn test_no_guard() {
+        assert!(Day6::part1("..#\n...").is_err());
+    }
+}
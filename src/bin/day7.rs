@@ -0,0 +1,307 @@
+use std::process::ExitCode;
+
+use adventofcode2024::digits::Digits;
+use adventofcode2024::error::{parse_as, parse_lines, AocError};
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day7;
+
+/// A left-associative operator that can also be undone from the right, which is what makes the
+/// search cheap: most operators can only have produced a given result in at most one way.
+trait Operator {
+    fn symbol(&self) -> &str;
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64>;
+    /// The `lhs` for which `apply(lhs, rhs) == result`, if there is exactly one.
+    fn invert(&self, result: u64, rhs: u64) -> Option<u64>;
+    /// Whether `apply(lhs, rhs) == result` for every `lhs`, so that what comes before doesn't
+    /// matter.
+    fn absorbs(&self, _result: u64, _rhs: u64) -> bool {
+        false
+    }
+}
+
+struct Add;
+
+impl Operator for Add {
+    fn symbol(&self) -> &str {
+        "+"
+    }
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_add(rhs)
+    }
+    fn invert(&self, result: u64, rhs: u64) -> Option<u64> {
+        result.checked_sub(rhs)
+    }
+}
+
+struct Mul;
+
+impl Operator for Mul {
+    fn symbol(&self) -> &str {
+        "*"
+    }
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.checked_mul(rhs)
+    }
+    /// Only possible if `rhs` divides the result. A zero `rhs` could have come from anything,
+    /// which [`Operator::absorbs`] covers instead.
+    fn invert(&self, result: u64, rhs: u64) -> Option<u64> {
+        (rhs != 0 && result.is_multiple_of(rhs)).then(|| result / rhs)
+    }
+    fn absorbs(&self, result: u64, rhs: u64) -> bool {
+        rhs == 0 && result == 0
+    }
+}
+
+/// Writes the digits of `rhs` after those of `lhs`.
+struct Concat;
+
+impl Operator for Concat {
+    fn symbol(&self) -> &str {
+        "||"
+    }
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        lhs.concat(rhs)
+    }
+    /// Only possible if the result ends with the digits of `rhs`, and has more digits before them.
+    fn invert(&self, result: u64, rhs: u64) -> Option<u64> {
+        let (before, last) = result.split_low(rhs.num_digits());
+        (last == rhs && before > 0).then_some(before)
+    }
+}
+
+struct Equation {
+    target: u64,
+    operands: Vec<u64>,
+}
+
+impl Equation {
+    /// Operators to put between the operands, evaluated left to right, that produce the target.
+    ///
+    /// This works from the right: the last operator must be one that can be undone given the
+    /// target and the last operand, which rules out most of them at each step.
+    fn solve<'a>(&self, ops: &[&'a dyn Operator]) -> Option<Vec<&'a dyn Operator>> {
+        let (first, rest) = self.operands.split_first()?;
+        Self::solve_from(self.target, *first, rest, ops)
+    }
+
+    /// Solves `first op rest[0] op ... = target`.
+    fn solve_from<'a>(
+        target: u64,
+        first: u64,
+        rest: &[u64],
+        ops: &[&'a dyn Operator],
+    ) -> Option<Vec<&'a dyn Operator>> {
+        let Some((last, init)) = rest.split_last() else {
+            return (target == first).then(Vec::new);
+        };
+        ops.iter().find_map(|op| {
+            let mut assignment = if op.absorbs(target, *last) {
+                // Whatever the operands before come to works, as long as they come to something.
+                let mut assignment = vec![];
+                Self::evaluate_any(first, init, ops, &mut assignment).then_some(assignment)?
+            } else {
+                let prev = op.invert(target, *last)?;
+                Self::solve_from(prev, first, init, ops)?
+            };
+            assignment.push(*op);
+            Some(assignment)
+        })
+    }
+
+    /// Searches forwards for operators that evaluate `acc op rest[0] op ...` without failing,
+    /// appending them to `assignment`.
+    fn evaluate_any<'a>(
+        acc: u64,
+        rest: &[u64],
+        ops: &[&'a dyn Operator],
+        assignment: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        let Some((next, tail)) = rest.split_first() else {
+            return true;
+        };
+        for op in ops {
+            if let Some(acc) = op.apply(acc, *next) {
+                assignment.push(*op);
+                if Self::evaluate_any(acc, tail, ops, assignment) {
+                    return true;
+                }
+                assignment.pop();
+            }
+        }
+        false
+    }
+
+    /// Evaluates the operands with `assignment`, left to right.
+    fn evaluate(&self, assignment: &[&dyn Operator]) -> Option<u64> {
+        let (first, rest) = self.operands.split_first()?;
+        rest.iter()
+            .zip(assignment)
+            .try_fold(*first, |acc, (rhs, op)| op.apply(acc, *rhs))
+    }
+
+    fn render(&self, assignment: &[&dyn Operator]) -> String {
+        let mut out = format!("{}: {}", self.target, self.operands[0]);
+        for (rhs, op) in self.operands[1..].iter().zip(assignment) {
+            out.push_str(&format!(" {} {rhs}", op.symbol()));
+        }
+        out
+    }
+}
+
+fn parse_inputs(inp: &str) -> Result<Vec<Equation>, AocError> {
+    parse_lines(inp, |l| {
+        let (target, operands) = l
+            .split_once(": ")
+            .ok_or_else(|| AocError::parse("an equation like 190: 10 19", l))?;
+        let operands = operands
+            .split(' ')
+            .map(|it| parse_as(it, "an operand"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if operands.is_empty() {
+            return Err(AocError::invalid("an equation needs at least one operand"));
+        }
+        Ok(Equation {
+            target: parse_as(target, "a test value")?,
+            operands,
+        })
+    })
+}
+
+fn total_calibration(inp: &str, ops: &[&dyn Operator]) -> Result<u64, AocError> {
+    Ok(parse_inputs(inp)?
+        .iter()
+        .filter(|it| it.solve(ops).is_some())
+        .map(|it| it.target)
+        .sum())
+}
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        total_calibration(inp, &[&Add, &Mul]).map(Answer::from)
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        total_calibration(inp, &[&Add, &Mul, &Concat]).map(Answer::from)
+    }
+}
+
+/// Prints each equation that can be made true with all three operators, checking the operators
+/// found by evaluating them forwards.
+fn show(inp: &str) -> Result<(), AocError> {
+    let ops: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+    for eq in parse_inputs(inp)? {
+        if let Some(assignment) = eq.solve(&ops) {
+            if eq.evaluate(&assignment) != Some(eq.target) {
+                return Err(AocError::invalid(format!(
+                    "{} doesn't evaluate to the target",
+                    eq.render(&assignment)
+                )));
+            }
+            println!("{}", eq.render(&assignment));
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|it| it == "--show") {
+        return match Day7::input().and_then(|inp| show(&inp)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    solution::run::<Day7>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An operator defined by a pair of functions, for trying out rules that aren't built in.
+    struct Custom {
+        symbol: &'static str,
+        apply: fn(u64, u64) -> Option<u64>,
+        invert: fn(u64, u64) -> Option<u64>,
+    }
+
+    impl Operator for Custom {
+        fn symbol(&self) -> &str {
+            self.symbol
+        }
+        fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+            (self.apply)(lhs, rhs)
+        }
+        fn invert(&self, result: u64, rhs: u64) -> Option<u64> {
+            (self.invert)(result, rhs)
+        }
+    }
+
+    const EXAMPLE: &str = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n\
+                           161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day7::part1(EXAMPLE), Ok(Answer::Num(3749)));
+        assert_eq!(Day7::part2(EXAMPLE), Ok(Answer::Num(11387)));
+    }
+
+    #[test]
+    fn test_assignment() {
+        let ops: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+        for eq in parse_inputs(EXAMPLE).unwrap() {
+            if let Some(assignment) = eq.solve(&ops) {
+                assert_eq!(eq.evaluate(&assignment), Some(eq.target));
+            }
+        }
+        let eq = Equation {
+            target: 7290,
+            operands: vec![6, 8, 6, 15],
+        };
+        assert_eq!(eq.render(&eq.solve(&ops).unwrap()), "7290: 6 * 8 || 6 * 15");
+    }
+
+    #[test]
+    fn test_zero_operand() {
+        let ops: [&dyn Operator; 2] = [&Add, &Mul];
+        let eq = Equation {
+            target: 0,
+            operands: vec![3, 4, 0],
+        };
+        assert_eq!(eq.render(&eq.solve(&ops).unwrap()), "0: 3 + 4 * 0");
+        // The prefix has to evaluate to something: every way of combining these overflows.
+        let eq = Equation {
+            target: 0,
+            operands: vec![u64::MAX, u64::MAX, 0],
+        };
+        assert!(eq.solve(&ops).is_none());
+        let eq = Equation {
+            target: 5,
+            operands: vec![5, 0, 0],
+        };
+        assert_eq!(eq.render(&eq.solve(&ops).unwrap()), "5: 5 + 0 + 0");
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        let sub = Custom {
+            symbol: "-",
+            apply: |lhs, rhs| lhs.checked_sub(rhs),
+            invert: |result, rhs| result.checked_add(rhs),
+        };
+        let eq = Equation {
+            target: 3,
+            operands: vec![10, 2, 5],
+        };
+        assert!(eq.solve(&[&Add, &Mul]).is_none());
+        assert_eq!(
+            eq.render(&eq.solve(&[&Add, &sub]).unwrap()),
+            "3: 10 - 2 - 5"
+        );
+    }
+}
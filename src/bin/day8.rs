@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
+
+use adventofcode2024::coords::{Bounded, Coord, CoordDiff};
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+use itertools::Itertools;
+
+struct Day8;
+
+struct AntennaMap {
+    by_freq: HashMap<char, Vec<Coord>>,
+    size: CoordDiff,
+}
+
+impl Bounded for AntennaMap {
+    fn in_bounds(&self, c: Coord) -> bool {
+        c.row >= 0 && c.col >= 0 && c.row < self.size.rows && c.col < self.size.cols
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum AntinodeRule {
+    /// Points in line with a pair of antennas, twice as far from one as from the other.
+    TwiceDistance,
+    /// Every grid point in line with a pair of antennas.
+    Collinear,
+}
+
+impl AntennaMap {
+    /// Grid points from `start` onwards in steps of `step`, until they leave the map.
+    fn ray(&self, start: Coord, step: CoordDiff) -> impl Iterator<Item = Coord> + '_ {
+        std::iter::successors(Some(start), move |c| Some(*c + step))
+            .take_while(|c| self.in_bounds(*c))
+    }
+
+    fn antinodes_of(&self, a: Coord, b: Coord, rule: AntinodeRule) -> Vec<Coord> {
+        let diff = b - a;
+        match rule {
+            AntinodeRule::TwiceDistance => [a - diff, b + diff]
+                .into_iter()
+                .filter(|c| self.in_bounds(*c))
+                .collect(),
+            AntinodeRule::Collinear => {
+                // Reducing the step by the gcd finds the points between the antennas too.
+                let step = diff.reduced();
+                self.ray(a, step).chain(self.ray(a - step, -step)).collect()
+            }
+        }
+    }
+
+    fn antinodes(&self, rule: AntinodeRule) -> HashSet<Coord> {
+        self.by_freq
+            .values()
+            .flat_map(|antennas| antennas.iter().tuple_combinations())
+            .flat_map(|(a, b)| self.antinodes_of(*a, *b, rule))
+            .collect()
+    }
+}
+
+fn parse_inputs(inp: &str) -> Result<AntennaMap, AocError> {
+    let mut by_freq: HashMap<char, Vec<Coord>> = HashMap::new();
+    let mut size = CoordDiff { rows: 0, cols: 0 };
+    for (row, line) in inp.lines().enumerate() {
+        size.rows = row as i64 + 1;
+        size.cols = size.cols.max(line.len() as i64);
+        for (col, ch) in line.chars().enumerate() {
+            match ch {
+                '.' => {}
+                ch if ch.is_ascii_alphanumeric() => {
+                    by_freq
+                        .entry(ch)
+                        .or_default()
+                        .push(Coord::from_xy(col, row));
+                }
+                other => {
+                    return Err(AocError::parse("an antenna or .", other)
+                        .at_line(row + 1)
+                        .at_column(col + 1))
+                }
+            }
+        }
+    }
+    Ok(AntennaMap { by_freq, size })
+}
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        Ok(parse_inputs(inp)?
+            .antinodes(AntinodeRule::TwiceDistance)
+            .len()
+            .into())
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        Ok(parse_inputs(inp)?
+            .antinodes(AntinodeRule::Collinear)
+            .len()
+            .into())
+    }
+}
+
+fn main() -> ExitCode {
+    solution::run::<Day8>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> String {
+        std::fs::read_to_string("./inputs/day8.test.txt").unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Day8::part1(&example()), Ok(Answer::Num(14)));
+        assert_eq!(Day8::part2(&example()), Ok(Answer::Num(34)));
+    }
+
+    #[test]
+    fn test_input() {
+        let inp = Day8::input().unwrap();
+        assert_eq!(Day8::part1(&inp), Ok(Answer::Num(357)));
+        assert_eq!(Day8::part2(&inp), Ok(Answer::Num(1266)));
+    }
+
+    #[test]
+    fn test_collinear_between() {
+        // The antennas are 3 apart diagonally, so the points between them count too.
+        let map = parse_inputs("a...\n....\n....\n...a").unwrap();
+        assert_eq!(map.antinodes(AntinodeRule::TwiceDistance).len(), 0);
+        assert_eq!(map.antinodes(AntinodeRule::Collinear).len(), 4);
+        let map = parse_inputs("a.a...").unwrap();
+        assert_eq!(map.antinodes(AntinodeRule::Collinear).len(), 6);
+    }
+}
@@ -1,10 +1,58 @@
-use adventofcode2024::{Chunk, IntoChunkedIter};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::process::ExitCode;
 
-fn inputs() -> String {
-    std::fs::read_to_string("./inputs/day9.txt")
-        .unwrap()
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+use adventofcode2024::IntoChunkedIter;
+use itertools::Itertools;
+
+struct Day9;
+
+/// Splits the dense format into (file length, following space length) pairs. The last file has no
+/// space after it if the input has an odd number of digits.
+fn parse_dense(dense: &str) -> Result<Vec<(u32, Option<u32>)>, AocError> {
+    let digits = dense
         .trim()
-        .to_string()
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| {
+            c.to_digit(10)
+                .ok_or_else(|| AocError::parse("a digit", c).at_line(1).at_column(idx + 1))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(digits
+        .into_iter()
+        .into_chunked_strict::<2>()
+        .map(|chunk| match chunk {
+            Ok([f_len, sp_len]) => (f_len, Some(sp_len)),
+            Err(rest) => (rest[0], None),
+        })
+        .collect())
+}
+
+/// `len` blocks of `file` moving from block `from` to block `to`. Part 1 moves one block at a
+/// time; part 2 moves whole files.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Move {
+    file: u32,
+    from: usize,
+    to: usize,
+    len: usize,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "file {}: {}..{} -> {}",
+            self.file,
+            self.from,
+            self.from + self.len,
+            self.to
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -13,49 +61,143 @@ struct DriveMap {
 }
 
 impl DriveMap {
-    fn new(dense: &str) -> DriveMap {
+    fn new(dense: &str) -> Result<DriveMap, AocError> {
         let mut drivemap = vec![];
-        let mut f_idx: u32 = 0;
-        dense.chars().into_chunked::<2>().for_each(|chunk| {
-            match chunk {
-                Chunk::Complete([f_len, sp_len]) => {
-                    for _ in 0..f_len.to_digit(10).unwrap() {
-                        drivemap.push(Some(f_idx));
-                    }
-                    for _ in 0..sp_len.to_digit(10).unwrap() {
-                        drivemap.push(None);
-                    }
-                }
-                Chunk::Partial([Some(f_len), None]) => {
-                    for _ in 0..f_len.to_digit(10).unwrap() {
-                        drivemap.push(Some(f_idx));
-                    }
+        for (f_idx, (f_len, sp_len)) in (0..).zip(parse_dense(dense)?) {
+            for _ in 0..f_len {
+                drivemap.push(Some(f_idx));
+            }
+            for _ in 0..sp_len.unwrap_or_default() {
+                drivemap.push(None);
+            }
+        }
+        Ok(DriveMap { blocks: drivemap })
+    }
+
+    /// Reads the block notation, where each block is the id of the file in it or `.` if it's free.
+    fn parse_blocks(blocks: &str) -> Result<DriveMap, AocError> {
+        let blocks = blocks
+            .trim()
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| match c {
+                '.' => Ok(None),
+                c => c.to_digit(10).map(Some).ok_or_else(|| {
+                    AocError::parse("a digit or .", c)
+                        .at_line(1)
+                        .at_column(idx + 1)
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(DriveMap { blocks })
+    }
+
+    /// The block notation, which only has room for file ids below 10.
+    fn render(&self) -> Result<String, AocError> {
+        self.blocks
+            .iter()
+            .map(|b| match b {
+                None => Ok('.'),
+                Some(id) => char::from_digit(*id, 10).ok_or_else(|| {
+                    AocError::invalid(format!("file {id} doesn't fit in the block notation"))
+                }),
+            })
+            .collect()
+    }
+
+    /// The dense format. That can only describe layouts where the files are in id order, each in
+    /// one piece, and no file or gap is longer than 9 blocks.
+    fn to_dense(&self) -> Result<String, AocError> {
+        // File lengths at even indices and gap lengths at odd ones.
+        let mut lens = vec![];
+        for (block, run) in &self.blocks.iter().chunk_by(|it| **it) {
+            // Two files next to each other have an empty gap between them, and a gap where a file
+            // should be means that file is empty.
+            if block.is_some() != lens.len().is_multiple_of(2) {
+                lens.push(0);
+            }
+            if let Some(id) = block {
+                if id as usize != lens.len() / 2 {
+                    return Err(AocError::invalid(format!(
+                        "file {id} is out of order or in pieces"
+                    )));
                 }
-                other => panic!("Unexpected chunk: {other:?}"),
             }
-            f_idx += 1;
-        });
+            lens.push(run.count());
+        }
+        lens.into_iter()
+            .map(|len| {
+                u32::try_from(len)
+                    .ok()
+                    .and_then(|len| char::from_digit(len, 10))
+                    .ok_or_else(|| {
+                        AocError::invalid(format!("a run of {len} blocks doesn't fit in a digit"))
+                    })
+            })
+            .collect()
+    }
 
-        DriveMap { blocks: drivemap }
+    /// Replays a move, checking that the file is where it says and the blocks it moves to are free.
+    fn apply(&mut self, mv: &Move) -> Result<(), AocError> {
+        let (from, to) = (mv.from..mv.from + mv.len, mv.to..mv.to + mv.len);
+        let in_place = self
+            .blocks
+            .get(from.clone())
+            .is_some_and(|it| it.iter().all(|b| *b == Some(mv.file)));
+        if !in_place {
+            return Err(AocError::invalid(format!("{mv}: the file isn't there")));
+        }
+        let free = self
+            .blocks
+            .get(to.clone())
+            .is_some_and(|it| it.iter().all(|b| b.is_none()));
+        if !free {
+            return Err(AocError::invalid(format!("{mv}: the space isn't free")));
+        }
+        self.blocks[from].fill(None);
+        self.blocks[to].fill(Some(mv.file));
+        Ok(())
+    }
+
+    /// Moves blocks one at a time from the end of the disk into the first free block.
+    fn compact_blocks(&mut self) -> Vec<Move> {
+        let mut moves = vec![];
+        let mut emp = self.first_empty(0);
+        let mut full = self.last_filled(self.blocks.len());
+        // A disk with no free blocks, or no files, has nothing to move.
+        while let (Some(to), Some((from, file))) = (emp, full) {
+            if to >= from {
+                break;
+            }
+            moves.push(Move {
+                file,
+                from,
+                to,
+                len: 1,
+            });
+            self.blocks.swap(to, from);
+            emp = self.first_empty(to);
+            full = self.last_filled(from);
+        }
+        moves
     }
 
-    fn first_empty(&self, start: usize) -> usize {
+    /// The first free block from `start` on.
+    fn first_empty(&self, start: usize) -> Option<usize> {
         self.blocks
             .iter()
             .enumerate()
             .skip(start)
             .find_map(|(i, elt)| if elt.is_none() { Some(i) } else { None })
-            .unwrap()
     }
 
-    fn last_filled(&self, start_from_idx: usize) -> usize {
-        self.blocks
+    /// The last block before `end` with a file in it, and that file.
+    fn last_filled(&self, end: usize) -> Option<(usize, u32)> {
+        self.blocks[..end]
             .iter()
             .enumerate()
             .rev()
-            .skip(self.blocks.len() - 1 - start_from_idx)
-            .find_map(|(i, elt)| elt.map(|_| i))
-            .unwrap()
+            .find_map(|(i, elt)| elt.map(|file| (i, file)))
     }
 
     fn compute_checksum(&self) -> i64 {
@@ -99,71 +241,57 @@ impl ContiguousRegion {
     }
 }
 
+/// A strategy for part 2: each file, highest id first, moves once to the leftmost free span
+/// before it that's big enough, if there is one.
+trait Compactor: Sized {
+    fn new(dense: &str) -> Result<Self, AocError>;
+    /// Moves the files, returning the moves in the order they were made.
+    fn compact(&mut self) -> Vec<Move>;
+    fn compute_checksum(&self) -> i64;
+}
+
 #[derive(Debug)]
 struct DriveMapDefrag {
     blocks: Vec<ContiguousRegion>,
 }
 
 impl DriveMapDefrag {
-    fn new(dense: &str) -> Self {
-        let mut drivemap = vec![];
-        let mut f_idx: u32 = 0;
-        dense.chars().into_chunked::<2>().for_each(|chunk| {
-            match chunk {
-                Chunk::Complete([f_len, sp_len]) => {
-                    drivemap.push(ContiguousRegion::Filled {
-                        file_idx: f_idx,
-                        len: f_len.to_digit(10).unwrap() as usize,
-                    });
-                    drivemap.push(ContiguousRegion::Empty {
-                        len: sp_len.to_digit(10).unwrap() as usize,
-                    });
-                }
-                Chunk::Partial([Some(f_len), None]) => {
-                    drivemap.push(ContiguousRegion::Filled {
-                        file_idx: f_idx,
-                        len: f_len.to_digit(10).unwrap() as usize,
-                    });
-                }
-                other => panic!("Unexpected chunk: {other:?}"),
-            }
-            f_idx += 1;
-        });
-        DriveMapDefrag { blocks: drivemap }
+    /// The block the region at `idx` starts at.
+    fn position(&self, idx: usize) -> usize {
+        self.blocks[..idx].iter().map(|it| it.get_len()).sum()
     }
 
-    fn find_file(&self, idx: u32) -> usize {
+    /// The region holding file `idx` and its length.
+    fn find_file(&self, idx: u32) -> Option<(usize, usize)> {
         self.blocks
             .iter()
             .enumerate()
             .rev()
             .find_map(|(i, elt)| match elt {
-                ContiguousRegion::Filled { file_idx, .. } if *file_idx == idx => Some(i),
+                ContiguousRegion::Filled { file_idx, len } if *file_idx == idx => Some((i, *len)),
                 _ => None,
             })
-            .unwrap()
     }
 
-    fn find_first_empty(&self, with_capacity: usize) -> Option<usize> {
+    /// The first free region with room for `with_capacity` blocks, and its length.
+    fn find_first_empty(&self, with_capacity: usize) -> Option<(usize, usize)> {
         self.blocks
             .iter()
             .enumerate()
             .find_map(|(i, elt)| match elt {
-                ContiguousRegion::Empty { len } if *len >= with_capacity => Some(i),
+                ContiguousRegion::Empty { len } if *len >= with_capacity => Some((i, *len)),
                 _ => None,
             })
     }
 
-    fn swap(&mut self, space_idx: usize, file_idx: usize) {
-        let (file_size, file_num) = match self.blocks[file_idx] {
-            ContiguousRegion::Empty { .. } => panic!("Expected file"),
-            ContiguousRegion::Filled { len, file_idx } => (len, file_idx),
-        };
-        let space_size = match self.blocks[space_idx] {
-            ContiguousRegion::Empty { len } => len,
-            ContiguousRegion::Filled { .. } => panic!("Expected space"),
-        };
-        assert!(space_size >= file_size);
+    /// Moves file `file_num`, `file_size` blocks long at region `file_idx`, into the free region
+    /// at `space_idx`, which is `space_size` blocks long.
+    fn swap(
+        &mut self,
+        (space_idx, space_size): (usize, usize),
+        (file_idx, file_size): (usize, usize),
+        file_num: u32,
+    ) {
         if space_size == file_size {
             self.blocks.swap(space_idx, file_idx);
         } else {
@@ -182,15 +310,64 @@ impl DriveMapDefrag {
     }
 
     fn compact_space(&mut self) {
-        for i in 0..(self.blocks.len() - 1) {
-            if self.blocks[i].is_space() && self.blocks[i + 1].is_space() {
-                let new_len = self.blocks[i].get_len() + self.blocks[i + 1].get_len();
-                self.blocks[i].set_len(new_len);
-                self.blocks.remove(i + 1);
+        for i in 1..self.blocks.len() {
+            if self.blocks[i - 1].is_space() && self.blocks[i].is_space() {
+                let new_len = self.blocks[i - 1].get_len() + self.blocks[i].get_len();
+                self.blocks[i - 1].set_len(new_len);
+                self.blocks.remove(i);
                 return self.compact_space();
             }
         }
     }
+}
+
+impl Compactor for DriveMapDefrag {
+    fn new(dense: &str) -> Result<Self, AocError> {
+        let mut drivemap = vec![];
+        for (f_idx, (f_len, sp_len)) in (0..).zip(parse_dense(dense)?) {
+            drivemap.push(ContiguousRegion::Filled {
+                file_idx: f_idx,
+                len: f_len as usize,
+            });
+            if let Some(sp_len) = sp_len {
+                drivemap.push(ContiguousRegion::Empty {
+                    len: sp_len as usize,
+                });
+            }
+        }
+        Ok(DriveMapDefrag { blocks: drivemap })
+    }
+
+    fn compact(&mut self) -> Vec<Move> {
+        let mut moves = vec![];
+        let mut curr_file = self
+            .blocks
+            .iter()
+            .map(|b| match b {
+                ContiguousRegion::Empty { .. } => 0,
+                ContiguousRegion::Filled { file_idx, .. } => *file_idx,
+            })
+            .max()
+            .unwrap_or(0);
+        // we don't actually need to do index 0 anyway since it's at the start of the file.
+        while curr_file > 0 {
+            let file = self.find_file(curr_file);
+            let space = file.and_then(|(_, file_len)| self.find_first_empty(file_len));
+            if let (Some(file), Some(space)) = (file, space) {
+                if space.0 < file.0 {
+                    moves.push(Move {
+                        file: curr_file,
+                        from: self.position(file.0),
+                        to: self.position(space.0),
+                        len: file.1,
+                    });
+                    self.swap(space, file, curr_file);
+                }
+            }
+            curr_file -= 1;
+        }
+        moves
+    }
 
     fn compute_checksum(&self) -> i64 {
         let mut checksum = 0;
@@ -212,45 +389,280 @@ impl DriveMapDefrag {
     }
 }
 
-fn part1(inp: &str) -> i64 {
-    let mut dm = DriveMap::new(inp);
-    let mut emp = dm.first_empty(0);
-    let mut full = dm.last_filled(dm.blocks.len() - 1);
-    while emp < full {
-        dm.blocks.swap(emp, full);
-        emp = dm.first_empty(emp);
-        full = dm.last_filled(full);
-    }
-    dm.compute_checksum()
+/// Keeps the free spans in a min-heap of start positions for each length, so the leftmost span
+/// that fits a file is the smallest top among the heaps for lengths at least the file's. Each
+/// move is then a few heap operations instead of a scan of the whole disk.
+///
+/// Space freed by a move never needs indexing: every file still to move starts before it.
+#[derive(Debug)]
+struct HeapCompactor {
+    /// (start, length) of each file, indexed by file id.
+    files: Vec<(usize, usize)>,
+    free: [BinaryHeap<Reverse<usize>>; 10],
 }
 
-fn part2(inp: &str) -> i64 {
-    let mut dm = DriveMapDefrag::new(inp);
-    let mut curr_file = dm
-        .blocks
-        .iter()
-        .map(|b| match b {
-            ContiguousRegion::Empty { .. } => 0,
-            ContiguousRegion::Filled { file_idx, .. } => *file_idx,
-        })
-        .max()
-        .unwrap();
-    // we don't actually need to do index 0 anyway since it's at the start of the file.
-    while curr_file > 0 {
-        let file_pos = dm.find_file(curr_file);
-        let file_len = dm.blocks[file_pos].get_len();
-        let space_pos = dm.find_first_empty(file_len);
-        if let Some(sp) = space_pos {
-            if sp < file_pos {
-                dm.swap(sp, file_pos);
+impl Compactor for HeapCompactor {
+    fn new(dense: &str) -> Result<Self, AocError> {
+        let mut files = vec![];
+        let mut free: [BinaryHeap<Reverse<usize>>; 10] = Default::default();
+        let mut pos = 0;
+        for (f_len, sp_len) in parse_dense(dense)? {
+            files.push((pos, f_len as usize));
+            pos += f_len as usize;
+            let sp_len = sp_len.unwrap_or_default() as usize;
+            if sp_len > 0 {
+                free[sp_len].push(Reverse(pos));
+            }
+            pos += sp_len;
+        }
+        Ok(HeapCompactor { files, free })
+    }
+
+    fn compact(&mut self) -> Vec<Move> {
+        let mut moves = vec![];
+        for (file, (start, len)) in self.files.iter_mut().enumerate().rev() {
+            if *len == 0 {
+                continue;
+            }
+            let best = (*len..self.free.len())
+                .filter_map(|size| self.free[size].peek().map(|Reverse(pos)| (*pos, size)))
+                .filter(|(pos, _)| pos < start)
+                .min();
+            if let Some((pos, size)) = best {
+                self.free[size].pop();
+                moves.push(Move {
+                    file: file as u32,
+                    from: *start,
+                    to: pos,
+                    len: *len,
+                });
+                *start = pos;
+                if size > *len {
+                    self.free[size - *len].push(Reverse(pos + *len));
+                }
             }
         }
-        curr_file -= 1;
+        moves
+    }
+
+    fn compute_checksum(&self) -> i64 {
+        (0..)
+            .zip(self.files.iter())
+            .map(|(id, (start, len))| {
+                let (start, len) = (*start as i64, *len as i64);
+                id * (start * len + len * (len - 1) / 2)
+            })
+            .sum()
+    }
+}
+
+fn part1(inp: &str) -> Result<i64, AocError> {
+    let mut dm = DriveMap::new(inp)?;
+    dm.compact_blocks();
+    Ok(dm.compute_checksum())
+}
+
+fn part2<C: Compactor>(inp: &str) -> Result<i64, AocError> {
+    let mut dm = C::new(inp)?;
+    dm.compact();
+    Ok(dm.compute_checksum())
+}
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    fn part1(inp: &str) -> Result<Answer, AocError> {
+        part1(inp).map(Answer::from)
+    }
+
+    fn part2(inp: &str) -> Result<Answer, AocError> {
+        part2::<HeapCompactor>(inp).map(Answer::from)
     }
-    dm.compute_checksum()
 }
 
-fn main() {
-    println!("Part 1: {}", part1(&inputs()));
-    println!("Part 2: {}", part2(&inputs()));
+/// Prints each move part 2 makes, and the disk after it if the ids are small enough to draw,
+/// then replays them on the original layout to check they give the same checksum. The layout can
+/// be given in the dense format or the block notation.
+fn print_trace(layout: &str) -> Result<(), AocError> {
+    let dense = if layout.contains('.') {
+        DriveMap::parse_blocks(layout)?.to_dense()?
+    } else {
+        layout.to_string()
+    };
+    let mut compactor = HeapCompactor::new(&dense)?;
+    let moves = compactor.compact();
+    let mut dm = DriveMap::new(&dense)?;
+    if let Ok(blocks) = dm.render() {
+        println!("{blocks}");
+    }
+    for mv in moves.iter() {
+        dm.apply(mv)?;
+        match dm.render() {
+            Ok(blocks) => println!("{blocks}  {mv}"),
+            Err(_) => println!("{mv}"),
+        }
+    }
+    if dm.compute_checksum() != compactor.compute_checksum() {
+        return Err(AocError::invalid(format!(
+            "replaying the moves gives checksum {}, not {}",
+            dm.compute_checksum(),
+            compactor.compute_checksum()
+        )));
+    }
+    println!("{} moves, checksum: {}", moves.len(), dm.compute_checksum());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip_while(|it| it != "--trace");
+    if args.next().is_some() {
+        let layout = args.next().map_or_else(Day9::input, Ok);
+        return match layout.and_then(|it| print_trace(&it)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    // Runs part 2 with the original linear-scan compactor, for comparison.
+    if std::env::args().any(|it| it == "--linear") {
+        return match Day9::input().and_then(|inp| part2::<DriveMapDefrag>(&inp)) {
+            Ok(checksum) => {
+                println!("Part 2: {checksum}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    solution::run::<Day9>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2333133121414131402";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(EXAMPLE), Ok(1928));
+        assert_eq!(part2::<DriveMapDefrag>(EXAMPLE), Ok(2858));
+        assert_eq!(part2::<HeapCompactor>(EXAMPLE), Ok(2858));
+    }
+
+    #[test]
+    fn test_degenerate_disks() {
+        // Empty, a single file, only free space, no free space, and an empty first file.
+        for (dense, checksum) in [("", 0), ("1", 0), ("05", 0), ("101", 1), ("0512", 0)] {
+            assert_eq!(part1(dense), Ok(checksum), "{dense}");
+            assert_eq!(part2::<DriveMapDefrag>(dense), Ok(checksum), "{dense}");
+            assert_eq!(part2::<HeapCompactor>(dense), Ok(checksum), "{dense}");
+        }
+    }
+
+    #[test]
+    fn test_compactors_agree() {
+        // A span that fills up exactly, one that's left with a remainder, and a file that doesn't
+        // fit anywhere to its left.
+        for dense in ["1313212", "22222", "9090909", "1910101", "0351122"] {
+            assert_eq!(
+                part2::<DriveMapDefrag>(dense),
+                part2::<HeapCompactor>(dense),
+                "{dense}"
+            );
+            let linear = DriveMapDefrag::new(dense).unwrap().compact();
+            assert_eq!(
+                linear,
+                HeapCompactor::new(dense).unwrap().compact(),
+                "{dense}"
+            );
+        }
+    }
+
+    /// Replays `moves` on the layout `dense` describes, rendering it before the first and after
+    /// each one.
+    fn replay(dense: &str, moves: &[Move]) -> Vec<String> {
+        let mut dm = DriveMap::new(dense).unwrap();
+        let mut states = vec![dm.render().unwrap()];
+        for mv in moves {
+            dm.apply(mv).unwrap();
+            states.push(dm.render().unwrap());
+        }
+        states
+    }
+
+    #[test]
+    fn test_worked_examples() {
+        let moves = DriveMap::new("12345").unwrap().compact_blocks();
+        assert_eq!(
+            replay("12345", &moves),
+            vec![
+                "0..111....22222",
+                "02.111....2222.",
+                "022111....222..",
+                "0221112...22...",
+                "02211122..2....",
+                "022111222......",
+            ]
+        );
+        let moves = HeapCompactor::new(EXAMPLE).unwrap().compact();
+        assert_eq!(
+            moves[0],
+            Move {
+                file: 9,
+                from: 40,
+                to: 2,
+                len: 2
+            }
+        );
+        assert_eq!(
+            replay(EXAMPLE, &moves),
+            vec![
+                "00...111...2...333.44.5555.6666.777.888899",
+                "0099.111...2...333.44.5555.6666.777.8888..",
+                "0099.1117772...333.44.5555.6666.....8888..",
+                "0099.111777244.333....5555.6666.....8888..",
+                "00992111777.44.333....5555.6666.....8888..",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notation_round_trip() {
+        let dm = DriveMap::new(EXAMPLE).unwrap();
+        let blocks = dm.render().unwrap();
+        assert_eq!(blocks, "00...111...2...333.44.5555.6666.777.888899");
+        assert_eq!(
+            DriveMap::parse_blocks(&blocks).unwrap().to_dense(),
+            Ok(EXAMPLE.into())
+        );
+        // Empty files and files with nothing between them.
+        assert_eq!(
+            DriveMap::parse_blocks("..12").unwrap().to_dense(),
+            Ok("02101".into())
+        );
+        assert!(DriveMap::parse_blocks("0.2.1").unwrap().to_dense().is_err());
+        assert!(DriveMap::parse_blocks("0000000000")
+            .unwrap()
+            .to_dense()
+            .is_err());
+        let mut dm = DriveMap::new("12345").unwrap();
+        let mv = Move {
+            file: 1,
+            from: 3,
+            to: 1,
+            len: 3,
+        };
+        assert!(dm.apply(&mv).is_err());
+    }
+
+    #[test]
+    fn test_bad_digit() {
+        let err = DriveMap::new("12x4").unwrap_err();
+        assert_eq!(err.column, Some(3));
+    }
 }
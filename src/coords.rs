@@ -1,5 +1,7 @@
 use std::fmt::Debug;
-use std::ops::{Add, AddAssign, Mul, Rem, Sub};
+use std::ops::{Add, AddAssign, Mul, Neg, Rem, Sub};
+
+use crate::numtheory::gcd;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Coord {
@@ -136,6 +138,17 @@ impl CoordDiff {
     pub fn norm_1(&self) -> i64 {
         self.cols.abs() + self.rows.abs()
     }
+    /// The smallest step in the same direction that still lands on whole coordinates, i.e. this
+    /// divided by the gcd of its components. Zero stays zero.
+    pub fn reduced(&self) -> CoordDiff {
+        match gcd(self.rows, self.cols) {
+            0 => *self,
+            g => CoordDiff {
+                rows: self.rows / g,
+                cols: self.cols / g,
+            },
+        }
+    }
     pub fn from_xy<X: TryInto<i64>, Y: TryInto<i64>>(x: X, y: Y) -> Self
     where
         X::Error: Debug,
@@ -266,3 +279,25 @@ impl Sub<Self> for Coord {
         }
     }
 }
+
+impl Sub<CoordDiff> for Coord {
+    type Output = Coord;
+
+    fn sub(self, rhs: CoordDiff) -> Self::Output {
+        Coord {
+            row: self.row - rhs.rows,
+            col: self.col - rhs.cols,
+        }
+    }
+}
+
+impl Neg for CoordDiff {
+    type Output = CoordDiff;
+
+    fn neg(self) -> Self::Output {
+        CoordDiff {
+            rows: -self.rows,
+            cols: -self.cols,
+        }
+    }
+}
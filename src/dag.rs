@@ -0,0 +1,280 @@
+//! Counting paths and reachable sinks in the directed graph a grid induces, where each cell has
+//! an edge to the neighbours a successor predicate accepts.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::coords::{Bounded, Coord};
+use crate::error::AocError;
+
+/// A fixed-size set of small integers, one bit each.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn with_capacity(bits: usize) -> BitSet {
+        BitSet {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    pub fn insert(&mut self, bit: usize) {
+        self.words[bit / 64] |= 1 << (bit % 64);
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        self.words
+            .get(bit / 64)
+            .is_some_and(|it| it & (1 << (bit % 64)) != 0)
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|it| it.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|it| *it == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.words.len() * 64).filter(|bit| self.contains(*bit))
+    }
+}
+
+/// An unsigned integer to count paths with, failing rather than wrapping if there are too many.
+pub trait PathCount: Copy + Eq {
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! path_count {
+    ($($t:ty),*) => {
+        $(impl PathCount for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        })*
+    };
+}
+
+path_count!(u32, u64, u128, usize);
+
+/// Path counts for every node reachable from the sources, and the sinks each source can reach.
+#[derive(Clone, Debug)]
+pub struct Paths<C> {
+    /// The sinks, in the order their bits appear in the reachable sets.
+    pub sinks: Vec<Coord>,
+    counts: HashMap<Coord, C>,
+    /// Only the sources' sets are kept; the others are dropped as soon as nothing needs them.
+    reachable: HashMap<Coord, BitSet>,
+}
+
+impl<C: PathCount> Paths<C> {
+    /// The number of paths from `c` that end at a sink, or zero if `c` wasn't reached.
+    pub fn count(&self, c: Coord) -> C {
+        self.counts.get(&c).copied().unwrap_or(C::ZERO)
+    }
+
+    /// The sinks some path from `c` ends at, if `c` is one of the sources; none otherwise.
+    pub fn reachable_sinks(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.reachable
+            .get(&c)
+            .into_iter()
+            .flat_map(|it| it.iter())
+            .map(|idx| self.sinks[idx])
+    }
+
+    pub fn num_reachable_sinks(&self, c: Coord) -> usize {
+        self.reachable.get(&c).map_or(0, |it| it.len())
+    }
+}
+
+/// Counts the paths from every node reachable from `sources` to `sinks`, over the graph with an
+/// edge from each cell to each of its `N` neighbours (4 or 8) that `successor(from, to)` accepts.
+/// A path ends at every sink it passes through, so one running through a sink to another counts
+/// for both.
+///
+/// The graph has to be acyclic for the counts to be finite; if it isn't, the error names a cycle.
+/// Counts that don't fit in `C` are also an error.
+pub fn count_paths<const N: u8, C: PathCount, B: Bounded>(
+    grid: &B,
+    successor: impl Fn(Coord, Coord) -> bool,
+    sources: impl IntoIterator<Item = Coord>,
+    sinks: impl IntoIterator<Item = Coord>,
+) -> Result<Paths<C>, AocError> {
+    let successors = |c: Coord| -> Vec<Coord> {
+        c.iter_neighbors::<_, N>(grid)
+            .filter(|n| successor(c, *n))
+            .collect()
+    };
+    let sources: HashSet<Coord> = sources.into_iter().collect();
+    let order = topological_order(sources.iter().copied(), successors)?;
+    // How many nodes still need each node's reachable set; once none do, it can go.
+    let mut waiting: HashMap<Coord, usize> = HashMap::new();
+    for c in order.iter() {
+        for next in successors(*c) {
+            *waiting.entry(next).or_default() += 1;
+        }
+    }
+    let sinks: Vec<Coord> = sinks.into_iter().collect();
+    let sink_idx: HashMap<Coord, usize> = sinks.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let mut counts: HashMap<Coord, C> = HashMap::new();
+    let mut reachable: HashMap<Coord, BitSet> = HashMap::new();
+    // Every node comes after all of its successors.
+    for c in order {
+        let mut count = C::ZERO;
+        let mut reach = BitSet::with_capacity(sinks.len());
+        if let Some(idx) = sink_idx.get(&c) {
+            count = C::ONE;
+            reach.insert(*idx);
+        }
+        for next in successors(c) {
+            count = count.checked_add(counts[&next]).ok_or_else(|| {
+                AocError::invalid(format!(
+                    "too many paths from ({}, {}) to count",
+                    c.row, c.col
+                ))
+            })?;
+            reach.union_with(&reachable[&next]);
+            let left = waiting.get_mut(&next).unwrap();
+            *left -= 1;
+            if *left == 0 && !sources.contains(&next) {
+                reachable.remove(&next);
+            }
+        }
+        counts.insert(c, count);
+        reachable.insert(c, reach);
+    }
+    Ok(Paths {
+        sinks,
+        counts,
+        reachable,
+    })
+}
+
+enum Mark {
+    Open,
+    Done,
+}
+
+/// The nodes reachable from `sources`, each after all of its successors, found with a depth-first
+/// search. Reaching a node that's still open means the path back to it is a cycle.
+fn topological_order(
+    sources: impl IntoIterator<Item = Coord>,
+    successors: impl Fn(Coord) -> Vec<Coord>,
+) -> Result<Vec<Coord>, AocError> {
+    let mut marks: HashMap<Coord, Mark> = HashMap::new();
+    let mut order = vec![];
+    for source in sources {
+        if marks.contains_key(&source) {
+            continue;
+        }
+        marks.insert(source, Mark::Open);
+        let mut stack = vec![(source, successors(source), 0)];
+        while let Some((c, next, idx)) = stack.last_mut() {
+            let Some(n) = next.get(*idx).copied() else {
+                marks.insert(*c, Mark::Done);
+                order.push(*c);
+                stack.pop();
+                continue;
+            };
+            *idx += 1;
+            match marks.get(&n) {
+                None => {
+                    marks.insert(n, Mark::Open);
+                    stack.push((n, successors(n), 0));
+                }
+                Some(Mark::Open) => {
+                    let start = stack.iter().position(|(it, _, _)| *it == n).unwrap();
+                    let cycle: Vec<_> = stack[start..]
+                        .iter()
+                        .map(|(it, _, _)| format!("({}, {})", it.row, it.col))
+                        .collect();
+                    return Err(AocError::invalid(format!(
+                        "the successors form a cycle: {} -> ({}, {})",
+                        cycle.join(" -> "),
+                        n.row,
+                        n.col
+                    )));
+                }
+                Some(Mark::Done) => {}
+            }
+        }
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Square(i64);
+
+    impl Bounded for Square {
+        fn in_bounds(&self, c: Coord) -> bool {
+            (0..self.0).contains(&c.row) && (0..self.0).contains(&c.col)
+        }
+    }
+
+    fn right_or_down(from: Coord, to: Coord) -> bool {
+        to == from.r() || to == from.d()
+    }
+
+    #[test]
+    fn test_lattice_paths() {
+        let grid = Square(3);
+        let origin = Coord { row: 0, col: 0 };
+        let corner = Coord { row: 2, col: 2 };
+        let edge = Coord { row: 0, col: 2 };
+        let paths: Paths<u64> =
+            count_paths::<4, _, _>(&grid, right_or_down, [origin], [corner, edge]).unwrap();
+        // Six paths to the far corner, one to the top-right, and the one through the top-right
+        // that carries on down counts for both.
+        assert_eq!(paths.count(origin), 7);
+        assert_eq!(paths.count(edge), 2);
+        assert_eq!(paths.count(Coord { row: 2, col: 0 }), 1);
+        assert_eq!(paths.num_reachable_sinks(origin), 2);
+        assert_eq!(
+            paths.reachable_sinks(origin).collect::<Vec<_>>(),
+            vec![corner, edge]
+        );
+        // Only the source's set outlives the search.
+        assert_eq!(paths.reachable.len(), 1);
+        assert_eq!(paths.num_reachable_sinks(Coord { row: 1, col: 0 }), 0);
+    }
+
+    #[test]
+    fn test_overflow() {
+        // There are 38 choose 19 = 35345263800 paths across a 20x20 grid, which needs more than
+        // 32 bits.
+        let grid = Square(20);
+        let origin = Coord { row: 0, col: 0 };
+        let corner = Coord { row: 19, col: 19 };
+        let narrow = count_paths::<4, u32, _>(&grid, right_or_down, [origin], [corner]);
+        assert!(narrow.is_err());
+        let wide = count_paths::<4, u64, _>(&grid, right_or_down, [origin], [corner]).unwrap();
+        assert_eq!(wide.count(origin), 35345263800);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let grid = Square(2);
+        let origin = Coord { row: 0, col: 0 };
+        let err = count_paths::<4, u64, _>(&grid, |_, _| true, [origin], []).unwrap_err();
+        assert!(err.to_string().contains("cycle: (0, 0) -> "), "{err}");
+        // Diagonal moves only reach one other cell from the corner.
+        let diagonal = |from: Coord, to: Coord| to == from.dr();
+        let paths = count_paths::<8, u64, _>(&grid, diagonal, [origin], [origin.dr()]).unwrap();
+        assert_eq!(paths.count(origin), 1);
+    }
+}
@@ -0,0 +1,109 @@
+//! Working with the decimal digits of unsigned integers arithmetically, without going through
+//! strings.
+
+/// Decimal digit operations. Zero has one digit.
+pub trait Digits: Copy + Sized {
+    /// `10^exp`, if it fits.
+    fn pow10(exp: u32) -> Option<Self>;
+
+    fn num_digits(self) -> u32;
+
+    /// Splits off the last `low` digits: `(self / 10^low, self % 10^low)`. If `10^low` doesn't
+    /// fit, every digit is in the low part.
+    fn split_low(self, low: u32) -> (Self, Self);
+
+    /// The first and second halves of the digits, if there are an even number of them.
+    fn split_half(self) -> Option<(Self, Self)> {
+        let n = self.num_digits();
+        n.is_multiple_of(2).then(|| self.split_low(n / 2))
+    }
+
+    /// The number written with the digits of `self` followed by those of `other`, if it fits.
+    fn concat(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! digits {
+    ($($t:ty),*) => {
+        $(impl Digits for $t {
+            fn pow10(exp: u32) -> Option<Self> {
+                (10 as $t).checked_pow(exp)
+            }
+
+            fn num_digits(self) -> u32 {
+                self.checked_ilog10().unwrap_or(0) + 1
+            }
+
+            fn split_low(self, low: u32) -> (Self, Self) {
+                match Self::pow10(low) {
+                    Some(pow) => (self / pow, self % pow),
+                    None => (0, self),
+                }
+            }
+
+            fn concat(self, other: Self) -> Option<Self> {
+                self.checked_mul(Self::pow10(other.num_digits())?)?
+                    .checked_add(other)
+            }
+        })*
+    };
+}
+
+digits!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values around every power of ten, plus some pseudo-random ones across the whole range.
+    fn samples() -> Vec<u128> {
+        let mut values: Vec<u128> = (0..=1000).collect();
+        for exp in 0..=38 {
+            let pow = 10u128.pow(exp);
+            values.extend([pow - 1, pow, pow + 1]);
+        }
+        values.push(u128::MAX);
+        let mut x: u128 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..1000 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            values.push(x >> (x % 128));
+        }
+        values
+    }
+
+    #[test]
+    fn test_against_strings() {
+        for n in samples() {
+            let s = n.to_string();
+            assert_eq!(n.num_digits() as usize, s.len(), "{n}");
+            let expected_half = (s.len() % 2 == 0).then(|| {
+                let (hi, lo) = s.split_at(s.len() / 2);
+                (hi.parse().unwrap(), lo.parse().unwrap())
+            });
+            assert_eq!(n.split_half(), expected_half, "{n}");
+            let low = (n % 7) as u32;
+            let split = s.len().saturating_sub(low as usize);
+            let (hi, lo) = s.split_at(split);
+            assert_eq!(
+                n.split_low(low),
+                (hi.parse().unwrap_or(0), lo.parse().unwrap_or(0)),
+                "{n}"
+            );
+            for m in [0, 7, 12345, n] {
+                assert_eq!(n.concat(m), format!("{n}{m}").parse().ok(), "{n} {m}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_narrow_types() {
+        assert_eq!(255u8.num_digits(), 3);
+        assert_eq!(25u8.concat(5), Some(255));
+        assert_eq!(25u8.concat(6), None);
+        assert_eq!(u64::MAX.split_low(25), (0, u64::MAX));
+        assert_eq!(1234u16.split_half(), Some((12, 34)));
+        assert_eq!(1000u32.split_half(), Some((10, 0)));
+        assert_eq!(0usize.split_half(), None);
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// A piece of the input couldn't be interpreted as what was expected there.
+    Parse { expected: String, found: String },
+    /// The input parsed, but doesn't describe a puzzle we can solve.
+    Invalid(String),
+    /// There's no solution for this part in code (e.g. it was solved by hand).
+    Unsolved(String),
+    /// A search was stopped by its time budget or cancelled before finding an answer.
+    Interrupted(String),
+    /// The input couldn't be read.
+    Io(String),
+}
+
+/// An error from a solution, with the (1-based) input line and column it refers to where known.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AocError {
+    pub kind: ErrorKind,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl AocError {
+    fn new(kind: ErrorKind) -> Self {
+        AocError {
+            kind,
+            line: None,
+            column: None,
+        }
+    }
+    pub fn parse(expected: impl Into<String>, found: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Parse {
+            expected: expected.into(),
+            found: found.into(),
+        })
+    }
+    pub fn invalid(msg: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Invalid(msg.into()))
+    }
+    pub fn unsolved(msg: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unsolved(msg.into()))
+    }
+    pub fn interrupted(msg: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Interrupted(msg.into()))
+    }
+    pub fn io(msg: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Io(msg.into()))
+    }
+    /// Sets the line if it isn't already known, so that inner, more specific context wins.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = self.line.or(Some(line));
+        self
+    }
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = self.column.or(Some(column));
+        self
+    }
+    /// Shifts the line by `lines`, for errors from a section that doesn't start the input.
+    pub fn offset_lines(mut self, lines: usize) -> Self {
+        self.line = self.line.map(|l| l + lines);
+        self
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(l), Some(c)) => write!(f, "line {l}, column {c}: ")?,
+            (Some(l), None) => write!(f, "line {l}: ")?,
+            (None, Some(c)) => write!(f, "column {c}: ")?,
+            (None, None) => {}
+        }
+        match &self.kind {
+            ErrorKind::Parse { expected, found } => {
+                write!(f, "expected {expected}, found {found:?}")
+            }
+            ErrorKind::Invalid(msg) => write!(f, "{msg}"),
+            ErrorKind::Unsolved(msg) => write!(f, "no solution: {msg}"),
+            ErrorKind::Interrupted(msg) => write!(f, "stopped early: {msg}"),
+            ErrorKind::Io(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
+
+/// Parses `s` as a `T`, describing the failure as expecting `expected`.
+pub fn parse_as<T: FromStr>(s: &str, expected: &str) -> Result<T, AocError> {
+    s.parse().map_err(|_| AocError::parse(expected, s))
+}
+
+/// Applies `f` to each line of `inp`, attaching the line number to any error it returns.
+pub fn parse_lines<'a, T>(
+    inp: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, AocError>,
+) -> Result<Vec<T>, AocError> {
+    inp.lines()
+        .enumerate()
+        .map(|(idx, l)| f(l).map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::parse_fields;
+
+    #[test]
+    fn test_error_context() {
+        let err = parse_lines("1 2\n3 x", |l| parse_fields::<i32>(l, "an integer")).unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column, Some(3));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected an integer, found \"x\""
+        );
+    }
+}
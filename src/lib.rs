@@ -1,6 +1,23 @@
+pub mod answers;
 pub mod coords;
+pub mod dag;
+pub mod digits;
+pub mod error;
+pub mod linalg;
+pub mod multiset;
+pub mod numtheory;
 pub mod parsers;
+pub mod progress;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod sequence;
+pub mod solution;
+pub mod stats;
+pub mod table;
+pub mod wordsearch;
 
+use std::collections::VecDeque;
 use std::fmt::Debug;
 
 pub struct ChunkedIter<const N: usize, T: Iterator> {
@@ -12,13 +29,67 @@ impl<const N: usize, T: Iterator> ChunkedIter<N, T> {
         assert!(N > 0);
         Self { underlying }
     }
+
+    /// Yields complete chunks as `Ok` and a trailing partial chunk as `Err` with its items.
+    pub fn strict(self) -> StrictChunkedIter<N, T> {
+        StrictChunkedIter { inner: self }
+    }
 }
 
+/// A chunk of up to `N` items. A `Partial` chunk only ever comes last, and holds between 1 and
+/// `N - 1` items at the front of the array with `None` after them.
 pub enum Chunk<const N: usize, I> {
     Complete([I; N]),
     Partial([Option<I>; N]),
 }
 
+impl<const N: usize, I> Chunk<N, I> {
+    pub fn len(&self) -> usize {
+        match self {
+            Chunk::Complete(..) => N,
+            Chunk::Partial(inner) => inner.iter().take_while(|it| it.is_some()).count(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Chunk::Complete(..))
+    }
+
+    pub fn complete(self) -> Option<[I; N]> {
+        match self {
+            Chunk::Complete(inner) => Some(inner),
+            Chunk::Partial(..) => None,
+        }
+    }
+
+    /// The items of a partial chunk, or `None` if the chunk is complete.
+    pub fn remainder(self) -> Option<Vec<I>> {
+        match self {
+            Chunk::Complete(..) => None,
+            Chunk::Partial(inner) => Some(inner.into_iter().map_while(|it| it).collect()),
+        }
+    }
+
+    pub fn into_result(self) -> Result<[I; N], Vec<I>> {
+        match self {
+            Chunk::Complete(inner) => Ok(inner),
+            partial => Err(partial.remainder().unwrap_or_default()),
+        }
+    }
+
+    fn from_options(items: [Option<I>; N]) -> Self {
+        if items.iter().all(|it| it.is_some()) {
+            Chunk::Complete(items.map(|it| it.unwrap()))
+        } else {
+            Chunk::Partial(items)
+        }
+    }
+}
+
 impl<const N: usize, I: Debug> Debug for Chunk<N, I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -32,29 +103,79 @@ impl<const N: usize, T: Iterator> Iterator for ChunkedIter<N, T> {
     type Item = Chunk<N, T::Item>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(n) = self.underlying.next() {
-            let mut n_once = Some(n);
-            let next_arr = std::array::from_fn(|idx| {
-                if idx == 0 {
-                    Some(n_once.take().unwrap())
-                } else {
-                    assert!(idx < N);
-                    self.underlying.next()
-                }
-            });
-            if next_arr.iter().all(|it| it.is_some()) {
-                Some(Chunk::Complete(next_arr.map(|it| it.unwrap())))
+        let mut first = Some(self.underlying.next()?);
+        let next_arr = std::array::from_fn(|idx| {
+            if idx == 0 {
+                first.take()
             } else {
-                Some(Chunk::Partial(next_arr))
+                self.underlying.next()
             }
-        } else {
-            None
+        });
+        Some(Chunk::from_options(next_arr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.underlying.size_hint();
+        (lower.div_ceil(N), upper.map(|it| it.div_ceil(N)))
+    }
+}
+
+impl<const N: usize, T: ExactSizeIterator> ExactSizeIterator for ChunkedIter<N, T> {}
+
+impl<const N: usize, T: DoubleEndedIterator + ExactSizeIterator> DoubleEndedIterator
+    for ChunkedIter<N, T>
+{
+    /// Chunks are still aligned from the front, so the first chunk from the back is the partial
+    /// one, if there is one.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let len = self.underlying.len();
+        if len == 0 {
+            return None;
+        }
+        let take = match len % N {
+            0 => N,
+            rem => rem,
+        };
+        let mut next_arr = std::array::from_fn(|_| None);
+        for slot in next_arr[..take].iter_mut().rev() {
+            *slot = self.underlying.next_back();
         }
+        Some(Chunk::from_options(next_arr))
+    }
+}
+
+pub struct StrictChunkedIter<const N: usize, T: Iterator> {
+    inner: ChunkedIter<N, T>,
+}
+
+impl<const N: usize, T: Iterator> Iterator for StrictChunkedIter<N, T> {
+    type Item = Result<[T::Item; N], Vec<T::Item>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Chunk::into_result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<const N: usize, T: ExactSizeIterator> ExactSizeIterator for StrictChunkedIter<N, T> {}
+
+impl<const N: usize, T: DoubleEndedIterator + ExactSizeIterator> DoubleEndedIterator
+    for StrictChunkedIter<N, T>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(Chunk::into_result)
     }
 }
 
 pub trait IntoChunkedIter: Iterator + Sized {
     fn into_chunked<const N: usize>(self) -> ChunkedIter<N, Self>;
+
+    fn into_chunked_strict<const N: usize>(self) -> StrictChunkedIter<N, Self> {
+        self.into_chunked().strict()
+    }
 }
 
 impl<I: Iterator + Sized> IntoChunkedIter for I {
@@ -62,3 +183,166 @@ impl<I: Iterator + Sized> IntoChunkedIter for I {
         ChunkedIter::new(self)
     }
 }
+
+/// Overlapping (or, with `S > N`, gapped) windows of `N` consecutive items, starting every `S`
+/// items. Items are kept in a ring buffer and cloned into each window, so the underlying iterator
+/// is never collected. A trailing window with fewer than `N` items is not yielded.
+pub struct StepByChunks<const N: usize, const S: usize, T: Iterator> {
+    underlying: T,
+    buf: VecDeque<T::Item>,
+    started: bool,
+}
+
+/// Windows of `N` items advancing by one, like `slice::windows` but over any iterator.
+pub type WindowedIter<const N: usize, T> = StepByChunks<N, 1, T>;
+
+impl<const N: usize, const S: usize, T: Iterator> StepByChunks<N, S, T> {
+    fn new(underlying: T) -> Self {
+        assert!(N > 0 && S > 0);
+        Self {
+            underlying,
+            buf: VecDeque::with_capacity(N),
+            started: false,
+        }
+    }
+
+    fn remaining(&self, underlying: usize) -> usize {
+        if self.started {
+            underlying / S
+        } else if underlying >= N {
+            (underlying - N) / S + 1
+        } else {
+            0
+        }
+    }
+}
+
+impl<const N: usize, const S: usize, T: Iterator> Iterator for StepByChunks<N, S, T>
+where
+    T::Item: Clone,
+{
+    type Item = [T::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            self.buf.drain(..S.min(self.buf.len()));
+            if S > N {
+                self.underlying.nth(S - N - 1)?;
+            }
+        }
+        self.started = true;
+        while self.buf.len() < N {
+            self.buf.push_back(self.underlying.next()?);
+        }
+        Some(std::array::from_fn(|idx| self.buf[idx].clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.underlying.size_hint();
+        (self.remaining(lower), upper.map(|it| self.remaining(it)))
+    }
+}
+
+impl<const N: usize, const S: usize, T: ExactSizeIterator> ExactSizeIterator
+    for StepByChunks<N, S, T>
+where
+    T::Item: Clone,
+{
+}
+
+pub trait IntoWindowedIter: Iterator + Sized {
+    fn windowed<const N: usize>(self) -> WindowedIter<N, Self> {
+        StepByChunks::new(self)
+    }
+
+    fn step_by_chunks<const N: usize, const S: usize>(self) -> StepByChunks<N, S, Self> {
+        StepByChunks::new(self)
+    }
+}
+
+impl<I: Iterator + Sized> IntoWindowedIter for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_hint() {
+        let chunked = (0..7).into_chunked::<3>();
+        assert_eq!(chunked.len(), 3);
+        assert_eq!((0..6).into_chunked::<3>().len(), 2);
+        assert_eq!((0..0).into_chunked::<3>().len(), 0);
+        assert_eq!(
+            (0..7).filter(|_| true).into_chunked::<3>().size_hint(),
+            (0, Some(3))
+        );
+    }
+
+    #[test]
+    fn test_remainder() {
+        let chunks = (0..5).into_chunked::<3>().collect::<Vec<_>>();
+        assert_eq!(chunks[1].len(), 2);
+        let mut chunks = chunks.into_iter();
+        assert_eq!(chunks.next().unwrap().remainder(), None);
+        assert_eq!(chunks.next().unwrap().remainder(), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(
+            (0..5).into_chunked_strict::<2>().collect::<Vec<_>>(),
+            vec![Ok([0, 1]), Ok([2, 3]), Err(vec![4])]
+        );
+        assert_eq!(
+            "abcd"
+                .chars()
+                .into_chunked_strict::<2>()
+                .collect::<Vec<_>>(),
+            vec![Ok(['a', 'b']), Ok(['c', 'd'])]
+        );
+    }
+
+    #[test]
+    fn test_double_ended() {
+        assert_eq!(
+            (0..7).into_chunked_strict::<3>().rev().collect::<Vec<_>>(),
+            vec![Err(vec![6]), Ok([3, 4, 5]), Ok([0, 1, 2])]
+        );
+        let mut chunked = (0..6).into_chunked_strict::<2>();
+        assert_eq!(chunked.next_back(), Some(Ok([4, 5])));
+        assert_eq!(chunked.next(), Some(Ok([0, 1])));
+        assert_eq!(chunked.len(), 1);
+        assert_eq!(chunked.next_back(), Some(Ok([2, 3])));
+        assert_eq!(chunked.next(), None);
+    }
+
+    #[test]
+    fn test_windowed() {
+        let windows = (0..5).windowed::<3>();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(
+            windows.collect::<Vec<_>>(),
+            vec![[0, 1, 2], [1, 2, 3], [2, 3, 4]]
+        );
+        assert_eq!((0..2).windowed::<3>().count(), 0);
+        assert_eq!((0..2).windowed::<3>().len(), 0);
+    }
+
+    #[test]
+    fn test_step_by_chunks() {
+        let overlapping = (0..7).step_by_chunks::<3, 2>();
+        assert_eq!(overlapping.len(), 3);
+        assert_eq!(
+            overlapping.collect::<Vec<_>>(),
+            vec![[0, 1, 2], [2, 3, 4], [4, 5, 6]]
+        );
+        let gapped = (0..10).step_by_chunks::<2, 3>();
+        assert_eq!(gapped.len(), 3);
+        assert_eq!(gapped.collect::<Vec<_>>(), vec![[0, 1], [3, 4], [6, 7]]);
+        for len in 0..12 {
+            let mut windows = (0..len).step_by_chunks::<3, 4>();
+            let _ = windows.next();
+            assert_eq!(windows.len(), windows.count());
+        }
+    }
+}
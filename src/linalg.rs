@@ -0,0 +1,316 @@
+//! Exact solutions to small systems of linear equations, worked out over the rationals.
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::numtheory::gcd;
+
+/// A fraction in lowest terms with a positive denominator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Rational {
+        assert!(den != 0, "zero denominator");
+        let g = gcd(num, den) * den.signum();
+        Rational {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn num(&self) -> i128 {
+        self.num
+    }
+
+    pub fn den(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        -(-*self).floor()
+    }
+}
+
+macro_rules! rational_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Rational {
+            fn from(value: $t) -> Self {
+                Rational::new(value.into(), 1)
+            }
+        })*
+    };
+}
+
+rational_from_int!(i32, i64, i128);
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Rational::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Rational::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// Panics if `rhs` is zero.
+    fn div(self, rhs: Self) -> Self::Output {
+        Rational::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+/// The solutions to a system of linear equations.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Solutions {
+    None,
+    Unique(Vec<Rational>),
+    /// `particular + t_1 * directions[0] + t_2 * directions[1] + ...` for any values of the `t`s.
+    /// Each direction belongs to one of the unknowns the equations leave free: it has a 1 for
+    /// that unknown, and 0 for the other free ones, which are also 0 in `particular`.
+    Family {
+        particular: Vec<Rational>,
+        directions: Vec<Vec<Rational>>,
+    },
+}
+
+/// Solves `a x = b` by Gauss-Jordan elimination, for any number of equations (rows of `a`) and
+/// unknowns (columns).
+pub fn solve(a: &[Vec<Rational>], b: &[Rational]) -> Solutions {
+    assert_eq!(a.len(), b.len(), "one right-hand side per equation");
+    let unknowns = a.first().map_or(0, |it| it.len());
+    let mut rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, rhs)| {
+            assert_eq!(
+                row.len(),
+                unknowns,
+                "every equation needs the same unknowns"
+            );
+            row.iter().copied().chain([*rhs]).collect()
+        })
+        .collect();
+    let mut pivots = vec![];
+    for col in 0..unknowns {
+        let next = pivots.len();
+        let Some(found) = (next..rows.len()).find(|r| !rows[*r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(next, found);
+        let scale = rows[next][col];
+        for it in rows[next].iter_mut() {
+            *it = *it / scale;
+        }
+        let pivot = rows[next].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != next && !factor.is_zero() {
+                for (it, p) in row.iter_mut().zip(pivot.iter()).skip(col) {
+                    *it = *it - factor * *p;
+                }
+            }
+        }
+        pivots.push(col);
+    }
+    // Anything left over says 0 = rhs.
+    if rows[pivots.len()..]
+        .iter()
+        .any(|it| !it[unknowns].is_zero())
+    {
+        return Solutions::None;
+    }
+    let mut particular = vec![Rational::ZERO; unknowns];
+    for (row, col) in pivots.iter().enumerate() {
+        particular[*col] = rows[row][unknowns];
+    }
+    let directions: Vec<Vec<Rational>> = (0..unknowns)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut direction = vec![Rational::ZERO; unknowns];
+            direction[free] = Rational::ONE;
+            for (row, col) in pivots.iter().enumerate() {
+                direction[*col] = -rows[row][free];
+            }
+            direction
+        })
+        .collect();
+    if directions.is_empty() {
+        Solutions::Unique(particular)
+    } else {
+        Solutions::Family {
+            particular,
+            directions,
+        }
+    }
+}
+
+/// Solves `a x = b` for integer coefficients; the solutions can still be fractions.
+pub fn solve_integer(a: &[Vec<i64>], b: &[i64]) -> Solutions {
+    let a: Vec<Vec<Rational>> = a
+        .iter()
+        .map(|row| row.iter().map(|it| Rational::from(*it)).collect())
+        .collect();
+    let b: Vec<Rational> = b.iter().map(|it| Rational::from(*it)).collect();
+    solve(&a, &b)
+}
+
+pub fn solve_2x2(a: [[i64; 2]; 2], b: [i64; 2]) -> Solutions {
+    solve_integer(&[a[0].to_vec(), a[1].to_vec()], &b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den)
+    }
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(r(2, -4), r(-1, 2));
+        assert_eq!(r(1, 3) + r(1, 6), r(1, 2));
+        assert_eq!(r(3, 4) / r(3, 2), r(1, 2));
+        assert!(r(-1, 2) < r(1, 3));
+        assert_eq!((r(-7, 2).floor(), r(-7, 2).ceil()), (-4, -3));
+        assert_eq!(r(6, 3).to_integer(), Some(2));
+        assert_eq!(r(-5, 3).to_string(), "-5/3");
+    }
+
+    #[test]
+    fn test_2x2() {
+        assert_eq!(
+            solve_2x2([[94, 22], [34, 67]], [8400, 5400]),
+            Solutions::Unique(vec![80.into(), 40.into()])
+        );
+        assert_eq!(
+            solve_2x2([[1, 2], [3, 4]], [1, 1]),
+            Solutions::Unique(vec![(-1).into(), 1.into()])
+        );
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [1, 3]), Solutions::None);
+        assert_eq!(
+            solve_2x2([[2, 4], [1, 2]], [6, 3]),
+            Solutions::Family {
+                particular: vec![3.into(), 0.into()],
+                directions: vec![vec![(-2).into(), 1.into()]],
+            }
+        );
+        assert_eq!(
+            solve_2x2([[0, 0], [0, 0]], [0, 0]),
+            Solutions::Family {
+                particular: vec![0.into(), 0.into()],
+                directions: vec![vec![1.into(), 0.into()], vec![0.into(), 1.into()]],
+            }
+        );
+    }
+
+    #[test]
+    fn test_nxn() {
+        let a = vec![vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        assert_eq!(
+            solve_integer(&a, &[8, -11, -3]),
+            Solutions::Unique(vec![2.into(), 3.into(), (-1).into()])
+        );
+        assert_eq!(
+            solve_integer(&[vec![3, 0], vec![0, 2]], &[1, 1]),
+            Solutions::Unique(vec![r(1, 3), r(1, 2)])
+        );
+        // The third equation is the sum of the first two, so one unknown is left free.
+        let a = vec![vec![1, 2, 3], vec![0, 1, 1], vec![1, 3, 4]];
+        let b = [6, 2, 8];
+        let Solutions::Family {
+            particular,
+            directions,
+        } = solve_integer(&a, &b)
+        else {
+            panic!("expected a family of solutions");
+        };
+        assert_eq!(directions.len(), 1);
+        for t in [0, 1, -5] {
+            let x: Vec<Rational> = particular
+                .iter()
+                .zip(&directions[0])
+                .map(|(p, d)| *p + Rational::from(t as i64) * *d)
+                .collect();
+            for (row, rhs) in a.iter().zip(b) {
+                let lhs = row
+                    .iter()
+                    .zip(&x)
+                    .fold(Rational::ZERO, |acc, (c, x)| acc + Rational::from(*c) * *x);
+                assert_eq!(lhs, rhs.into());
+            }
+        }
+        assert_eq!(solve_integer(&a, &[6, 2, 9]), Solutions::None);
+    }
+}
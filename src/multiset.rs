@@ -0,0 +1,157 @@
+//! Simulating a population of items that each evolve independently, by tracking how many of each
+//! distinct item there are rather than every item.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::error::AocError;
+
+/// The population after a generation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub size: u128,
+    pub distinct: usize,
+}
+
+/// A multiset of items where every generation, each item is replaced by what `rule` turns it
+/// into. The rule is only called once for each distinct item, however many generations it shows
+/// up in. Counts that don't fit in a `u128` are an error rather than wrapping.
+pub struct MultisetEvolver<T, F> {
+    rule: F,
+    cache: HashMap<T, Vec<T>>,
+    population: HashMap<T, u128>,
+    history: Vec<GenerationStats>,
+}
+
+impl<T, F, I> MultisetEvolver<T, F>
+where
+    T: Hash + Eq + Clone,
+    F: Fn(T) -> I,
+    I: IntoIterator<Item = T>,
+{
+    pub fn new(initial: impl IntoIterator<Item = T>, rule: F) -> Result<Self, AocError> {
+        let mut population = HashMap::new();
+        for item in initial {
+            add(&mut population, item, 1)?;
+        }
+        let mut evolver = MultisetEvolver {
+            rule,
+            cache: HashMap::new(),
+            population,
+            history: vec![],
+        };
+        evolver.history.push(evolver.measure(0)?);
+        Ok(evolver)
+    }
+
+    fn measure(&self, generation: usize) -> Result<GenerationStats, AocError> {
+        let size = self
+            .population
+            .values()
+            .try_fold(0u128, |acc, n| acc.checked_add(*n))
+            .ok_or_else(|| overflow(generation))?;
+        Ok(GenerationStats {
+            generation,
+            size,
+            distinct: self.population.len(),
+        })
+    }
+
+    /// Moves on one generation. If the counts overflow, the population is left as it was.
+    pub fn step(&mut self) -> Result<GenerationStats, AocError> {
+        let generation = self.generation() + 1;
+        let mut next = HashMap::new();
+        for (item, count) in self.population.iter() {
+            let children = self
+                .cache
+                .entry(item.clone())
+                .or_insert_with_key(|item| (self.rule)(item.clone()).into_iter().collect());
+            for child in children.iter() {
+                add(&mut next, child.clone(), *count).map_err(|_| overflow(generation))?;
+            }
+        }
+        // The new generation's size can overflow even if no single count does.
+        let previous = std::mem::replace(&mut self.population, next);
+        let stats = match self.measure(generation) {
+            Ok(stats) => stats,
+            Err(e) => {
+                self.population = previous;
+                return Err(e);
+            }
+        };
+        self.history.push(stats);
+        Ok(stats)
+    }
+
+    /// Moves on `n` generations, returning the stats after each one.
+    pub fn run(&mut self, n: usize) -> Result<Vec<GenerationStats>, AocError> {
+        (0..n).map(|_| self.step()).collect()
+    }
+
+    pub fn generation(&self) -> usize {
+        self.history.len() - 1
+    }
+
+    /// The number of items in the current generation.
+    pub fn size(&self) -> u128 {
+        self.history.last().unwrap().size
+    }
+
+    /// The stats for every generation so far, starting with the initial population.
+    pub fn history(&self) -> &[GenerationStats] {
+        &self.history
+    }
+
+    pub fn population(&self) -> &HashMap<T, u128> {
+        &self.population
+    }
+
+    /// How many distinct items the rule has been applied to.
+    pub fn rule_calls(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+fn add<T: Hash + Eq>(population: &mut HashMap<T, u128>, item: T, n: u128) -> Result<(), AocError> {
+    let count = population.entry(item).or_default();
+    *count = count.checked_add(n).ok_or_else(|| overflow(0))?;
+    Ok(())
+}
+
+fn overflow(generation: usize) -> AocError {
+    AocError::invalid(format!(
+        "the population is too big to count in a u128 by generation {generation}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evolve() {
+        // Fibonacci's rabbits: each adult pair has a young pair every generation, and young pairs
+        // grow up.
+        let rule = |adult: bool| if adult { vec![true, false] } else { vec![true] };
+        let mut rabbits = MultisetEvolver::new([false], rule).unwrap();
+        let stats = rabbits.run(10).unwrap();
+        let sizes: Vec<_> = stats.iter().map(|it| it.size).collect();
+        assert_eq!(sizes, vec![1, 2, 3, 5, 8, 13, 21, 34, 55, 89]);
+        assert_eq!(rabbits.generation(), 10);
+        assert_eq!(rabbits.history()[0].size, 1);
+        assert_eq!(rabbits.history()[1].distinct, 1);
+        assert_eq!(rabbits.history()[2].distinct, 2);
+        assert_eq!(rabbits.population()[&true], 55);
+        assert_eq!(rabbits.rule_calls(), 2);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut doubling = MultisetEvolver::new([()], |()| [(), ()]).unwrap();
+        let err = doubling.run(200).unwrap_err();
+        assert!(err.to_string().contains("generation 128"), "{err}");
+        assert_eq!(doubling.size(), 1 << 127);
+        assert_eq!(doubling.population()[&()], 1 << 127);
+    }
+}
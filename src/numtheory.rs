@@ -0,0 +1,144 @@
+//! Integer number theory: gcds, modular inverses and the Chinese Remainder Theorem. `gcd` works
+//! for any signed integer type and `ext_gcd` and `mod_inverse` in `i128`; `mod_pow` and `crt` take
+//! `i64`s but work out intermediate products in `i128`, so any `i64` moduli are fine.
+
+use std::ops::{Neg, Rem};
+
+/// Always non-negative; `gcd(0, 0)` is 0. Works for any signed integer type, e.g. `i128` for
+/// [`crate::linalg`]'s fractions.
+pub fn gcd<T>(a: T, b: T) -> T
+where
+    T: Copy + Ord + Default + Rem<Output = T> + Neg<Output = T>,
+{
+    let zero = T::default();
+    let abs = |x: T| if x < zero { -x } else { x };
+    let (mut a, mut b) = (abs(a), abs(b));
+    while b != zero {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Always non-negative; 0 if either argument is. Panics if it doesn't fit in an `i64`.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+/// `(g, x, y)` with `a * x + b * y == g`, where `g` is `gcd(a, b)`.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime. `m` must be positive.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    assert!(m > 0, "modulus must be positive");
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base^exp mod m`, in `0..m`. `m` must be positive.
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    assert!(m > 0, "modulus must be positive");
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Combines congruences `x ≡ r (mod m)` into one, `x ≡ r (mod lcm of the m's)`, with `r` in
+/// `0..lcm`. The moduli don't need to be coprime, but then the congruences can contradict each
+/// other, which gives `None`, as does an lcm too big for an `i64`. Moduli must be positive.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut acc: (i128, i128) = (0, 1);
+    for (r, m) in congruences.iter().copied() {
+        assert!(m > 0, "modulus must be positive");
+        let (r, m) = ((r as i128).rem_euclid(m as i128), m as i128);
+        let (r0, m0) = acc;
+        // x = r0 + m0 * k, so we need m0 * k ≡ r - r0 (mod m).
+        let g = gcd(m0, m);
+        if (r - r0) % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let inv = mod_inverse((m0 / g) % step, step)?;
+        let k = ((r - r0) / g).rem_euclid(step) * inv % step;
+        let modulus = m0 * step;
+        acc = ((r0 + m0 * k).rem_euclid(modulus), modulus);
+        if modulus > i64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((acc.0 as i64, acc.1 as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(-(1i128 << 100), 6 << 90), 1 << 91);
+        assert_eq!(lcm(4, -6), 12);
+        assert_eq!(lcm(0, 5), 0);
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (5, 0), (101, 103)] {
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        // A modulus too big for an i64.
+        let m = (1i128 << 89) - 1;
+        assert_eq!(mod_inverse(3, m).map(|inv| 3 * inv % m), Some(1));
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 7), 6);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        // Fermat's little theorem, with products that overflow an i64.
+        let p = 1_000_000_007;
+        assert_eq!(mod_pow(123_456_789, p as u64 - 1, p), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 101), (-1, 103)]), Some((10402, 10403)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        for x in 0..60 {
+            assert_eq!(crt(&[(x % 4, 4), (x % 6, 6), (x % 10, 10)]), Some((x, 60)));
+        }
+    }
+}
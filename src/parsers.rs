@@ -3,6 +3,8 @@ use std::marker::PhantomData;
 
 use regex::Regex;
 
+use crate::error::AocError;
+
 #[derive(Eq, PartialEq)]
 pub enum ParseResult<'a, T: Eq> {
     Success { value: T, rest: &'a str },
@@ -29,6 +31,14 @@ impl<T: Eq> ParseResult<'_, T> {
             Self::Failure => panic!("Unwrap of parsing failure"),
         }
     }
+
+    /// Like `unwrap_value`, but reports a failure as an error expecting `expected` in `input`.
+    pub fn value_or_error(self, expected: &str, input: &str) -> Result<T, AocError> {
+        match self {
+            Self::Success { value, .. } => Ok(value),
+            Self::Failure => Err(AocError::parse(expected, input)),
+        }
+    }
 }
 
 pub trait Parser: Sized {
@@ -0,0 +1,189 @@
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::error::AocError;
+
+/// Checking the clock and redrawing on every tick would dominate tight search loops, so that only
+/// happens once per this many ticks.
+const CHECK_EVERY: u64 = 1 << 12;
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StopReason {
+    OutOfTime,
+    Cancelled,
+}
+
+/// Why and where a search was stopped, for reporting what it had got through.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Interrupted {
+    pub reason: StopReason,
+    pub ticks: u64,
+    pub elapsed: Duration,
+    pub note: Option<String>,
+}
+
+impl Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            StopReason::OutOfTime => write!(f, "ran out of time")?,
+            StopReason::Cancelled => write!(f, "cancelled")?,
+        }
+        write!(f, " after {} steps in {:.2?}", self.ticks, self.elapsed)?;
+        if let Some(note) = &self.note {
+            write!(f, " ({note})")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Interrupted> for AocError {
+    fn from(value: Interrupted) -> Self {
+        AocError::interrupted(value.to_string())
+    }
+}
+
+/// Progress reporting and early stopping for long-running loops. Call [`Progress::tick`] once per
+/// step; it returns an error once the time budget is spent or the cancel token is triggered.
+pub struct Progress {
+    bar: ProgressBar,
+    start: Instant,
+    ticks: u64,
+    budget: Option<Duration>,
+    cancel: Option<CancelToken>,
+    note: Option<String>,
+}
+
+impl Progress {
+    /// A spinner on stderr when it's a terminal, otherwise silent.
+    pub fn new(label: &str) -> Self {
+        if !std::io::stderr().is_terminal() {
+            return Self::silent();
+        }
+        let bar = ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} {prefix}: {human_pos} steps {msg} [{elapsed}]")
+                .unwrap(),
+        );
+        bar.set_prefix(label.to_string());
+        Self::with_bar(bar)
+    }
+
+    pub fn silent() -> Self {
+        Self::with_bar(ProgressBar::hidden())
+    }
+
+    fn with_bar(bar: ProgressBar) -> Self {
+        Progress {
+            bar,
+            start: Instant::now(),
+            ticks: 0,
+            budget: None,
+            cancel: None,
+            note: None,
+        }
+    }
+
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Records a diagnostic (e.g. the best candidate so far), shown alongside the progress and
+    /// included in the error if the search is stopped.
+    pub fn note(&mut self, note: impl Into<String>) {
+        let note = note.into();
+        self.bar.set_message(note.clone());
+        self.note = Some(note);
+    }
+
+    pub fn tick(&mut self) -> Result<(), Interrupted> {
+        self.ticks += 1;
+        if self.ticks.is_multiple_of(CHECK_EVERY) {
+            self.check()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check(&mut self) -> Result<(), Interrupted> {
+        self.bar.set_position(self.ticks);
+        let reason = if self.cancel.as_ref().is_some_and(|it| it.is_cancelled()) {
+            StopReason::Cancelled
+        } else if self.budget.is_some_and(|it| self.start.elapsed() >= it) {
+            StopReason::OutOfTime
+        } else {
+            return Ok(());
+        };
+        self.bar.abandon();
+        Err(Interrupted {
+            reason,
+            ticks: self.ticks,
+            elapsed: self.start.elapsed(),
+            note: self.note.clone(),
+        })
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel() {
+        let token = CancelToken::new();
+        let mut progress = Progress::silent().with_cancel(token.clone());
+        for _ in 0..CHECK_EVERY {
+            progress.tick().unwrap();
+        }
+        token.cancel();
+        progress.note("best so far: 3");
+        let err = (0..CHECK_EVERY)
+            .try_for_each(|_| progress.tick())
+            .unwrap_err();
+        assert_eq!(err.reason, StopReason::Cancelled);
+        assert_eq!(err.ticks, 2 * CHECK_EVERY);
+        assert_eq!(err.note.as_deref(), Some("best so far: 3"));
+    }
+
+    #[test]
+    fn test_budget() {
+        let mut progress = Progress::silent().with_budget(Duration::ZERO);
+        let err = std::iter::repeat(())
+            .try_for_each(|_| progress.tick())
+            .unwrap_err();
+        assert_eq!(err.reason, StopReason::OutOfTime);
+        assert_eq!(err.ticks, CHECK_EVERY);
+    }
+}
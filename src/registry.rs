@@ -0,0 +1,8 @@
+/// Days with a `src/bin/dayN.rs` solution that the `aoc` runner knows how to run.
+pub const DAYS: &[u32] = &[
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+];
+
+pub fn bin_name(day: u32) -> String {
+    format!("day{day}")
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::answers::Part;
+use crate::registry;
+
+#[derive(Clone, Debug)]
+pub struct DayRun {
+    pub day: u32,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub success: bool,
+    pub stderr: String,
+    pub elapsed: Duration,
+}
+
+impl DayRun {
+    pub fn answer(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    /// Whether the solution said it has no answer for `part`, rather than failing to find one.
+    pub fn skipped(&self, part: Part) -> bool {
+        let prefix = format!("Part {part} skipped: ");
+        self.stderr.lines().any(|it| it.starts_with(&prefix))
+    }
+}
+
+/// Pulls the `Part N: <answer>` lines out of a solution's output. Any other lines (debugging
+/// output, alternate solutions like `Part 1 (alt): ...`) are ignored.
+pub fn parse_output(stdout: &str) -> (Option<String>, Option<String>) {
+    let mut part1 = None;
+    let mut part2 = None;
+    for line in stdout.lines() {
+        if let Some(answer) = line.strip_prefix("Part 1: ") {
+            part1 = Some(answer.trim().to_string());
+        } else if let Some(answer) = line.strip_prefix("Part 2: ") {
+            part2 = Some(answer.trim().to_string());
+        }
+    }
+    (part1, part2)
+}
+
+/// The day binaries are built alongside the runner, so look for them next to it.
+pub fn bin_path(day: u32) -> std::io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    Ok(exe.with_file_name(format!(
+        "{}{}",
+        registry::bin_name(day),
+        std::env::consts::EXE_SUFFIX
+    )))
+}
+
+pub fn run_day(day: u32) -> std::io::Result<DayRun> {
+    let path = bin_path(day)?;
+    if !path.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "{} not found; build it with `cargo build --release --bins`",
+                path.display()
+            ),
+        ));
+    }
+    let start = Instant::now();
+    let output = Command::new(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+    let elapsed = start.elapsed();
+    let (part1, part2) = parse_output(&String::from_utf8_lossy(&output.stdout));
+    Ok(DayRun {
+        day,
+        part1,
+        part2,
+        success: output.status.success(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        elapsed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        let out = "debugging\nPart 1: 199946\nPart 1 (alt): 199946\nPart 2: 7,1,2\n";
+        assert_eq!(
+            parse_output(out),
+            (Some("199946".into()), Some("7,1,2".into()))
+        );
+        assert_eq!(parse_output("Part 1: 3\n"), (Some("3".into()), None));
+    }
+
+    #[test]
+    fn test_skipped() {
+        let run = DayRun {
+            day: 24,
+            part1: Some("3".into()),
+            part2: None,
+            success: true,
+            stderr: "Part 2 skipped: no solution: found by hand\n".into(),
+            elapsed: Duration::ZERO,
+        };
+        assert!(!run.skipped(Part::One));
+        assert!(run.skipped(Part::Two));
+    }
+}
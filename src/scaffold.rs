@@ -0,0 +1,221 @@
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::registry;
+
+const SOLUTION_TEMPLATE: &str = r#"use std::process::ExitCode;
+
+use adventofcode2024::error::AocError;
+use adventofcode2024::solution::{self, Answer, Solution};
+
+struct Day{{day}};
+
+impl Solution for Day{{day}} {
+    const DAY: u32 = {{day}};
+
+    fn part1(_inp: &str) -> Result<Answer, AocError> {
+        Err(AocError::unsolved("part 1 isn't written yet"))
+    }
+
+    fn part2(_inp: &str) -> Result<Answer, AocError> {
+        Err(AocError::unsolved("part 2 isn't written yet"))
+    }
+}
+
+fn main() -> ExitCode {
+    solution::run::<Day{{day}}>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "needs the example from the puzzle description"]
+    fn test_p1() {
+        assert_eq!(Day{{day}}::part1(EXAMPLE), Ok(Answer::Num(0)));
+    }
+}
+"#;
+
+const REGISTRY_START: &str = "pub const DAYS: &[u32] = &[";
+const REGISTRY_END: &str = "];";
+const MAX_WIDTH: usize = 100;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    AlreadyExists(PathBuf),
+    AlreadyRegistered(u32),
+    MalformedRegistry,
+    Io(std::io::Error),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaffoldError::AlreadyExists(p) => write!(f, "{} already exists", p.display()),
+            ScaffoldError::AlreadyRegistered(day) => {
+                write!(f, "day {day} is already in the registry")
+            }
+            ScaffoldError::MalformedRegistry => {
+                write!(f, "could not find the DAYS list in the registry")
+            }
+            ScaffoldError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+impl From<std::io::Error> for ScaffoldError {
+    fn from(value: std::io::Error) -> Self {
+        ScaffoldError::Io(value)
+    }
+}
+
+pub fn render_solution(day: u32) -> String {
+    SOLUTION_TEMPLATE.replace("{{day}}", &day.to_string())
+}
+
+/// Adds `day` to the `DAYS` list in the registry source, keeping it sorted and laid out the way
+/// rustfmt would.
+pub fn add_to_registry(source: &str, day: u32) -> Result<String, ScaffoldError> {
+    let start = source
+        .find(REGISTRY_START)
+        .ok_or(ScaffoldError::MalformedRegistry)?;
+    let body_start = start + REGISTRY_START.len();
+    let body_len = source[body_start..]
+        .find(REGISTRY_END)
+        .ok_or(ScaffoldError::MalformedRegistry)?;
+    let mut days = source[body_start..body_start + body_len]
+        .split(',')
+        .map(str::trim)
+        .filter(|it| !it.is_empty())
+        .map(|it| it.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ScaffoldError::MalformedRegistry)?;
+    if days.contains(&day) {
+        return Err(ScaffoldError::AlreadyRegistered(day));
+    }
+    days.push(day);
+    days.sort();
+
+    let mut lines: Vec<String> = vec![];
+    for d in days {
+        let entry = format!("{d},");
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + entry.len() <= MAX_WIDTH => {
+                line.push(' ');
+                line.push_str(&entry);
+            }
+            _ => lines.push(format!("    {entry}")),
+        }
+    }
+
+    Ok(format!(
+        "{}\n{}\n{}",
+        &source[..body_start],
+        lines.join("\n"),
+        &source[body_start + body_len..]
+    ))
+}
+
+fn create_new(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => ScaffoldError::AlreadyExists(path.to_path_buf()),
+            _ => ScaffoldError::Io(e),
+        })?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Creates `src/bin/dayN.rs`, an empty `inputs/dayN.txt` if there isn't one yet, and the
+/// registry entry, all relative to the repository `root`. Returns the paths that were written.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let bin_path = root
+        .join("src/bin")
+        .join(format!("{}.rs", registry::bin_name(day)));
+    if bin_path.exists() {
+        return Err(ScaffoldError::AlreadyExists(bin_path));
+    }
+    let registry_path = root.join("src/registry.rs");
+    let registry_source = add_to_registry(&std::fs::read_to_string(&registry_path)?, day)?;
+
+    create_new(&bin_path, &render_solution(day))?;
+    std::fs::write(&registry_path, registry_source)?;
+    let mut written = vec![bin_path, registry_path];
+
+    let input_path = root.join("inputs").join(format!("day{day}.txt"));
+    if !input_path.exists() {
+        create_new(&input_path, "")?;
+        written.push(input_path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_to_registry() {
+        let source = "/// Days.\npub const DAYS: &[u32] = &[\n    1, 3,\n];\n\nfn other() {}\n";
+        assert_eq!(
+            add_to_registry(source, 2).unwrap(),
+            "/// Days.\npub const DAYS: &[u32] = &[\n    1, 2, 3,\n];\n\nfn other() {}\n"
+        );
+        assert!(matches!(
+            add_to_registry(source, 3),
+            Err(ScaffoldError::AlreadyRegistered(3))
+        ));
+    }
+
+    #[test]
+    fn test_add_to_registry_wraps() {
+        let source = format!(
+            "pub const DAYS: &[u32] = &[{}];",
+            (1..40).map(|it| format!("{it}, ")).collect::<String>()
+        );
+        let updated = add_to_registry(&source, 40).unwrap();
+        assert!(updated.lines().all(|l| l.len() <= MAX_WIDTH));
+        assert!(updated.contains("39, 40,\n];"));
+    }
+
+    #[test]
+    fn test_real_registry_round_trips() {
+        let source = include_str!("registry.rs");
+        let day = (1..).find(|d| !registry::DAYS.contains(d)).unwrap();
+        let updated = add_to_registry(source, day).unwrap();
+        assert!(updated.contains(&format!(" {day},")));
+        assert!(updated.starts_with(&source[..source.find(REGISTRY_START).unwrap()]));
+    }
+
+    #[test]
+    fn test_refuses_existing_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src/bin")).unwrap();
+        std::fs::create_dir_all(root.join("inputs")).unwrap();
+        std::fs::write(root.join("src/registry.rs"), include_str!("registry.rs")).unwrap();
+
+        let written = new_day(&root, 99).unwrap();
+        assert_eq!(written.len(), 3);
+        assert!(std::fs::read_to_string(root.join("src/bin/day99.rs"))
+            .unwrap()
+            .contains("impl Solution for Day99"));
+        // A new day is registered straight away, so it mustn't panic when `aoc run all` runs it.
+        assert!(!render_solution(99).contains("todo!"));
+        assert!(matches!(
+            new_day(&root, 99),
+            Err(ScaffoldError::AlreadyExists(..))
+        ));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
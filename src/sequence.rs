@@ -0,0 +1,288 @@
+//! Validation of sequences against rules about consecutive items, optionally tolerating a few bad
+//! items that can be removed.
+
+/// A constraint that every item must satisfy relative to the item kept before it.
+pub trait PairRule<T> {
+    fn allows(&self, prev: &T, next: &T) -> bool;
+
+    /// Both this and `other` must allow each pair.
+    fn and<R: PairRule<T>>(self, other: R) -> Both<Self, R>
+    where
+        Self: Sized,
+    {
+        Both(self, other)
+    }
+}
+
+/// A rule for a whole sequence.
+pub trait SequenceRule<T> {
+    /// The index of the first item that can't be kept after the ones before it, if any.
+    fn first_violation(&self, items: &[T]) -> Option<usize>;
+
+    /// The indices of the fewest items (at most `max_removals`) whose removal makes `items`
+    /// valid, or `None` if that takes more removals.
+    fn min_removals(&self, items: &[T], max_removals: usize) -> Option<Vec<usize>>;
+
+    fn is_valid(&self, items: &[T]) -> bool {
+        self.first_violation(items).is_none()
+    }
+
+    /// The sequence must satisfy this rule or `other` throughout; unlike [`PairRule::and`], the
+    /// two can't be mixed within a sequence.
+    fn or<R: SequenceRule<T>>(self, other: R) -> Either<Self, R>
+    where
+        Self: Sized,
+    {
+        Either(self, other)
+    }
+}
+
+/// [`SequenceRule::first_violation`] for a [`PairRule`].
+fn pairwise_first_violation<T>(rule: &impl PairRule<T>, items: &[T]) -> Option<usize> {
+    (1..items.len()).find(|i| !rule.allows(&items[i - 1], &items[*i]))
+}
+
+/// [`SequenceRule::min_removals`] for a [`PairRule`].
+///
+/// `best[i]` is the fewest removals among the first `i + 1` items that keep item `i` last,
+/// along with the item kept before it. Fewer removals so far is always at least as good, so
+/// that's all that needs keeping, and only the `max_removals + 1` items before `i` can come
+/// before it. This makes the search linear for a fixed number of removals. Where removing
+/// either of two items works, the later one is removed.
+fn pairwise_min_removals<T>(
+    rule: &impl PairRule<T>,
+    items: &[T],
+    max_removals: usize,
+) -> Option<Vec<usize>> {
+    let n = items.len();
+    let k = max_removals;
+    let mut best: Vec<Option<(usize, Option<usize>)>> = Vec::with_capacity(n);
+    for i in 0..n {
+        let from_start = (i <= k).then_some((i, None));
+        let from_prev = (i.saturating_sub(k + 1)..i)
+            .filter(|p| rule.allows(&items[*p], &items[i]))
+            .filter_map(|p| best[p].map(|(r, _)| (r + i - p - 1, Some(p))))
+            .filter(|(r, _)| *r <= k);
+        best.push(from_start.into_iter().chain(from_prev).min());
+    }
+    let Some((last, _)) = (0..n)
+        .filter_map(|i| best[i].map(|(r, _)| (i, r + n - 1 - i)))
+        .filter(|(_, r)| *r <= k)
+        .min_by_key(|(_, r)| *r)
+    else {
+        // Only an empty sequence has nothing to keep.
+        return (n == 0).then(Vec::new);
+    };
+
+    let mut kept = vec![false; n];
+    let mut curr = Some(last);
+    while let Some(i) = curr {
+        kept[i] = true;
+        curr = best[i].and_then(|(_, prev)| prev);
+    }
+    Some((0..n).filter(|i| !kept[*i]).collect())
+}
+
+macro_rules! pairwise_sequence_rule {
+    () => {
+        fn first_violation(&self, items: &[T]) -> Option<usize> {
+            pairwise_first_violation(self, items)
+        }
+
+        fn min_removals(&self, items: &[T], max_removals: usize) -> Option<Vec<usize>> {
+            pairwise_min_removals(self, items, max_removals)
+        }
+    };
+}
+
+pub struct Both<A, B>(A, B);
+
+impl<T, A: PairRule<T>, B: PairRule<T>> PairRule<T> for Both<A, B> {
+    fn allows(&self, prev: &T, next: &T) -> bool {
+        self.0.allows(prev, next) && self.1.allows(prev, next)
+    }
+}
+
+impl<T, A: PairRule<T>, B: PairRule<T>> SequenceRule<T> for Both<A, B> {
+    pairwise_sequence_rule!();
+}
+
+pub struct Either<A, B>(A, B);
+
+impl<T, A: SequenceRule<T>, B: SequenceRule<T>> SequenceRule<T> for Either<A, B> {
+    /// The sequence is valid up to wherever the alternative that lasts longer fails.
+    fn first_violation(&self, items: &[T]) -> Option<usize> {
+        Some(
+            self.0
+                .first_violation(items)?
+                .max(self.1.first_violation(items)?),
+        )
+    }
+
+    fn min_removals(&self, items: &[T], max_removals: usize) -> Option<Vec<usize>> {
+        [
+            self.0.min_removals(items, max_removals),
+            self.1.min_removals(items, max_removals),
+        ]
+        .into_iter()
+        .flatten()
+        .min_by_key(|it| it.len())
+    }
+}
+
+/// Any relation between consecutive items, e.g. `|a, b| a != b`.
+pub struct Pairs<F>(pub F);
+
+impl<T, F: Fn(&T, &T) -> bool> PairRule<T> for Pairs<F> {
+    fn allows(&self, prev: &T, next: &T) -> bool {
+        (self.0)(prev, next)
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> bool> SequenceRule<T> for Pairs<F> {
+    pairwise_sequence_rule!();
+}
+
+pub fn pairs<T, F: Fn(&T, &T) -> bool>(f: F) -> Pairs<F> {
+    Pairs(f)
+}
+
+/// Strictly increasing.
+pub fn increasing<T: Ord>() -> Pairs<impl Fn(&T, &T) -> bool> {
+    pairs(|a: &T, b: &T| a < b)
+}
+
+/// Strictly decreasing.
+pub fn decreasing<T: Ord>() -> Pairs<impl Fn(&T, &T) -> bool> {
+    pairs(|a: &T, b: &T| a > b)
+}
+
+/// Strictly increasing throughout or strictly decreasing throughout.
+pub fn monotonic<T: Ord>() -> impl SequenceRule<T> {
+    increasing().or(decreasing())
+}
+
+/// Consecutive items differ by at least `min` and at most `max`, in either direction.
+pub fn bounded_deltas<T: Copy + Into<i64>>(min: i64, max: i64) -> Pairs<impl Fn(&T, &T) -> bool> {
+    pairs(move |a: &T, b: &T| (min..=max).contains(&((*b).into() - (*a).into()).abs()))
+}
+
+/// The outcome of checking a sequence while tolerating some removals.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Evaluation {
+    /// Valid once the items at these indices are removed (none if it's valid as is).
+    Valid { removed: Vec<usize> },
+    /// Not valid even with removals; this is the first violation with nothing removed.
+    Invalid { first_violation: usize },
+    /// Too short to be valid, whatever is removed.
+    TooShort,
+}
+
+impl Evaluation {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Evaluation::Valid { .. })
+    }
+}
+
+/// Whether `items` satisfies `rule` after removing at most `max_removals` of them while keeping
+/// at least `min_kept`, and if so which.
+pub fn evaluate<T>(
+    rule: &impl SequenceRule<T>,
+    items: &[T],
+    max_removals: usize,
+    min_kept: usize,
+) -> Evaluation {
+    if items.len() < min_kept {
+        return Evaluation::TooShort;
+    }
+    let Some(first_violation) = rule.first_violation(items) else {
+        return Evaluation::Valid { removed: vec![] };
+    };
+    match rule.min_removals(items, max_removals.min(items.len() - min_kept)) {
+        Some(removed) => Evaluation::Valid { removed },
+        None => Evaluation::Invalid { first_violation },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(rule: &impl SequenceRule<i32>, items: &[i32], k: usize) -> Option<usize> {
+        (0..1usize << items.len())
+            .filter(|mask| mask.count_ones() as usize <= k)
+            .filter(|mask| {
+                let kept: Vec<_> = (0..items.len())
+                    .filter(|i| mask & (1 << i) == 0)
+                    .map(|i| items[i])
+                    .collect();
+                rule.is_valid(&kept)
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let steps = || bounded_deltas(1, 3);
+        let rule = increasing().and(steps()).or(decreasing().and(steps()));
+        assert_eq!(
+            evaluate(&rule, &[1, 2, 7, 8, 9], 1, 0),
+            Evaluation::Invalid { first_violation: 2 }
+        );
+        assert_eq!(
+            evaluate(&rule, &[5, 1, 2, 3, 4], 1, 0),
+            Evaluation::Valid { removed: vec![0] }
+        );
+        assert_eq!(
+            evaluate(&rule, &[1, 3, 6, 7, 9], 0, 0),
+            Evaluation::Valid { removed: vec![] }
+        );
+        assert_eq!(monotonic().first_violation(&[1, 2, 3, 2, 1]), Some(3));
+    }
+
+    #[test]
+    fn test_min_kept() {
+        let rule = increasing().and(bounded_deltas(1, 3));
+        assert_eq!(
+            evaluate(&rule, &[1, 9], 1, 1),
+            Evaluation::Valid { removed: vec![1] }
+        );
+        // Removing either level would leave too few.
+        assert_eq!(
+            evaluate(&rule, &[1, 9], 1, 2),
+            Evaluation::Invalid { first_violation: 1 }
+        );
+        assert_eq!(
+            evaluate(&rule, &[1, 9, 4], 1, 2),
+            Evaluation::Valid { removed: vec![1] }
+        );
+        assert_eq!(evaluate(&rule, &[1], 1, 2), Evaluation::TooShort);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let rule = increasing()
+            .and(bounded_deltas(1, 2))
+            .or(decreasing().and(bounded_deltas(1, 2)));
+        let items = [3, 1, 2, 2, 5, 4, 6, 9, 7];
+        for k in 0..=3 {
+            for len in 0..=items.len() {
+                for start in 0..=items.len() - len {
+                    let items = &items[start..start + len];
+                    let fast = rule.min_removals(items, k).map(|it| it.len());
+                    assert_eq!(fast, brute_force(&rule, items, k), "{items:?} {k}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_predicate() {
+        let no_repeats = pairs(|a: &char, b: &char| a != b);
+        let items: Vec<_> = "abbcddd".chars().collect();
+        assert_eq!(no_repeats.first_violation(&items), Some(2));
+        assert_eq!(no_repeats.min_removals(&items, 3), Some(vec![2, 5, 6]));
+        assert_eq!(no_repeats.min_removals(&items, 2), None);
+    }
+}
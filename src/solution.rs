@@ -0,0 +1,99 @@
+use std::fmt::Display;
+use std::process::ExitCode;
+
+use crate::error::{AocError, ErrorKind};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Num(i128),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Num(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Num(value.into())
+            }
+        })*
+    };
+}
+
+answer_from_int!(i32, i64, u32, u64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Num(value as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+/// A day's puzzle. Implementors get input loading and the `Part N: ...` output format that the
+/// `aoc` runner expects via [`run`]. Malformed input should be reported as an [`AocError`] rather
+/// than a panic.
+pub trait Solution {
+    const DAY: u32;
+
+    fn input() -> Result<String, AocError> {
+        read_input(&format!("day{}.txt", Self::DAY))
+    }
+
+    fn part1(inp: &str) -> Result<Answer, AocError>;
+    fn part2(inp: &str) -> Result<Answer, AocError>;
+}
+
+/// Reads `name` from the `inputs` directory.
+pub fn read_input(name: &str) -> Result<String, AocError> {
+    let path = format!("./inputs/{name}");
+    std::fs::read_to_string(&path).map_err(|e| AocError::io(format!("couldn't read {path}: {e}")))
+}
+
+type PartFn = fn(&str) -> Result<Answer, AocError>;
+
+/// Runs both parts, printing each answer or error. A failure in part 1 doesn't stop part 2. A
+/// part that returns [`AocError::unsolved`] is reported as skipped, which isn't a failure.
+pub fn run<S: Solution>() -> ExitCode {
+    let inp = match S::input() {
+        Ok(inp) => inp,
+        Err(e) => {
+            eprintln!("Couldn't load day {} input: {e}", S::DAY);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut status = ExitCode::SUCCESS;
+    let parts: [PartFn; 2] = [S::part1, S::part2];
+    for (idx, solve) in parts.into_iter().enumerate() {
+        let part = idx + 1;
+        match solve(&inp) {
+            Ok(answer) => println!("Part {part}: {answer}"),
+            Err(e) if matches!(e.kind, ErrorKind::Unsolved(..)) => {
+                eprintln!("Part {part} skipped: {e}")
+            }
+            Err(e) => {
+                eprintln!("Part {part} failed: {e}");
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+    status
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Counts of how many times each item has been seen.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) {
+        *self.counts.entry(item).or_default() += n;
+    }
+
+    /// How many times `item` has been seen, zero if never.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The number of items seen, counting repeats.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(k, v)| (k, *v))
+    }
+
+    pub fn merge(&mut self, other: Counter<T>) {
+        for (item, n) in other.counts {
+            self.add_n(item, n);
+        }
+    }
+
+    /// The `n` most frequent items, most frequent first. Ties are broken by the smaller item.
+    pub fn most_common(&self, n: usize) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        let mut all: Vec<_> = self.iter().collect();
+        all.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        all.truncate(n);
+        all
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|it| self.add(it));
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+/// Pairs the smallest of `first` with the smallest of `second`, and so on. Extra items in the
+/// longer list are dropped.
+pub fn sorted_pairs<T: Ord>(mut first: Vec<T>, mut second: Vec<T>) -> impl Iterator<Item = (T, T)> {
+    first.sort();
+    second.sort();
+    first.into_iter().zip(second)
+}
+
+/// The sum of the distances between the sorted lists' items, position by position.
+pub fn sorted_total_distance<T: Ord + Into<i64>>(first: Vec<T>, second: Vec<T>) -> i64 {
+    sorted_pairs(first, second)
+        .map(|(a, b)| (a.into() - b.into()).abs())
+        .sum()
+}
+
+/// The largest distance between the sorted lists' items at the same position.
+pub fn sorted_max_distance<T: Ord + Into<i64>>(first: Vec<T>, second: Vec<T>) -> Option<i64> {
+    sorted_pairs(first, second)
+        .map(|(a, b)| (a.into() - b.into()).abs())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(
+            counter.most_common(3),
+            vec![(&'a', 5), (&'b', 2), (&'r', 2)]
+        );
+        counter.merge("zzz".chars().collect());
+        assert_eq!(counter.len(), 6);
+        assert_eq!(counter.total(), 14);
+        assert_eq!(counter.most_common(2), vec![(&'a', 5), (&'z', 3)]);
+    }
+
+    #[test]
+    fn test_sorted_distance() {
+        let first = vec![3, 4, 2, 1, 3, 3];
+        let second = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(sorted_total_distance(first.clone(), second.clone()), 11);
+        assert_eq!(sorted_max_distance(first, second), Some(5));
+    }
+}
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use crate::error::{parse_as, AocError};
+
+/// Splits `line` on runs of whitespace and pairs each field with its 1-based column.
+pub fn whitespace_columns(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .map(move |field| (field.as_ptr() as usize - line.as_ptr() as usize + 1, field))
+}
+
+/// Parses every whitespace-separated field of `line` as a `T`, attaching the column on failure.
+pub fn parse_fields<T: FromStr>(line: &str, expected: &str) -> Result<Vec<T>, AocError> {
+    whitespace_columns(line)
+        .map(|it| field(it, expected))
+        .collect()
+}
+
+/// A row type that can be built from the whitespace-separated fields of one line, e.g. a tuple of
+/// `FromStr` types.
+pub trait FromRow: Sized {
+    const WIDTH: usize;
+
+    /// Parses exactly [`FromRow::WIDTH`] fields, with the column of each.
+    fn from_fields(fields: &[(usize, &str)], expected: &str) -> Result<Self, AocError>;
+}
+
+fn field<T: FromStr>((col, s): (usize, &str), expected: &str) -> Result<T, AocError> {
+    parse_as(s, expected).map_err(|e| e.at_column(col))
+}
+
+macro_rules! from_row_tuple {
+    ($width:literal; $($t:ident $idx:tt),*) => {
+        impl<$($t: FromStr),*> FromRow for ($($t,)*) {
+            const WIDTH: usize = $width;
+
+            fn from_fields(fields: &[(usize, &str)], expected: &str) -> Result<Self, AocError> {
+                Ok(($(field::<$t>(fields[$idx], expected)?,)*))
+            }
+        }
+    };
+}
+
+from_row_tuple!(1; A 0);
+from_row_tuple!(2; A 0, B 1);
+from_row_tuple!(3; A 0, B 1, C 2);
+from_row_tuple!(4; A 0, B 1, C 2, D 3);
+
+/// Lazily parses each line of `inp` as a typed row, failing lines with the wrong number of fields.
+pub fn rows<'a, R: FromRow + 'a>(
+    inp: &'a str,
+    expected: &'a str,
+) -> impl Iterator<Item = Result<R, AocError>> + 'a {
+    inp.lines().enumerate().map(move |(idx, line)| {
+        let fields: Vec<_> = whitespace_columns(line).collect();
+        if fields.len() != R::WIDTH {
+            return Err(AocError::invalid(format!(
+                "expected {} columns, found {}",
+                R::WIDTH,
+                fields.len()
+            ))
+            .at_line(idx + 1));
+        }
+        R::from_fields(&fields, expected).map_err(|e| e.at_line(idx + 1))
+    })
+}
+
+pub fn parse_rows<R: FromRow>(inp: &str, expected: &str) -> Result<Vec<R>, AocError> {
+    rows(inp, expected).collect()
+}
+
+/// Parses a whitespace-separated table of any width, requiring every line to have as many fields
+/// as the first.
+pub fn parse_table<T: FromStr>(inp: &str, expected: &str) -> Result<Vec<Vec<T>>, AocError> {
+    let mut width = None;
+    inp.lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            let row: Vec<T> = parse_fields(line, expected).map_err(|e| e.at_line(idx + 1))?;
+            match width {
+                Some(w) if w != row.len() => Err(AocError::invalid(format!(
+                    "expected {w} columns, found {}",
+                    row.len()
+                ))
+                .at_line(idx + 1)),
+                _ => {
+                    width = Some(row.len());
+                    Ok(row)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Turns the rows of a rectangular table into its columns.
+pub fn transpose<T>(rows: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    let width = rows.first().map_or(0, |it| it.len());
+    let mut columns: Vec<Vec<T>> = (0..width).map(|_| Vec::with_capacity(rows.len())).collect();
+    for row in rows {
+        for (col, item) in columns.iter_mut().zip(row) {
+            col.push(item);
+        }
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whitespace_columns() {
+        assert_eq!(
+            whitespace_columns("  3   4\tx ").collect::<Vec<_>>(),
+            vec![(3, "3"), (7, "4"), (9, "x")]
+        );
+    }
+
+    #[test]
+    fn test_parse_rows() {
+        let rows: Vec<(i32, String)> = parse_rows("1 a\n2   b", "a field").unwrap();
+        assert_eq!(rows, vec![(1, "a".to_string()), (2, "b".to_string())]);
+        let err = parse_rows::<(i32, i32)>("1 2\n3 4 5", "a number").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        let err = parse_rows::<(i32, i32)>("1 2\n3 y", "a number").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(3)));
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let table: Vec<Vec<u8>> = parse_table("1 2 3\n4 5 6", "a number").unwrap();
+        assert_eq!(transpose(table), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert!(parse_table::<u8>("1 2 3\n4 5", "a number").is_err());
+    }
+}
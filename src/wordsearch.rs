@@ -0,0 +1,240 @@
+//! Finding words and small 2D shapes in a grid of letters, in any orientation.
+
+use crate::coords::{Bounded, Coord, CoordDiff};
+
+/// The eight directions a word can run in.
+pub const DIRECTIONS: [CoordDiff; 8] = [
+    CoordDiff { rows: 0, cols: 1 },
+    CoordDiff { rows: 1, cols: 1 },
+    CoordDiff { rows: 1, cols: 0 },
+    CoordDiff { rows: 1, cols: -1 },
+    CoordDiff { rows: 0, cols: -1 },
+    CoordDiff { rows: -1, cols: -1 },
+    CoordDiff { rows: -1, cols: 0 },
+    CoordDiff { rows: -1, cols: 1 },
+];
+
+pub struct WordSearch {
+    grid: Vec<Vec<char>>,
+}
+
+/// A word found starting at `start` and running in `direction`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct WordMatch {
+    pub start: Coord,
+    pub direction: CoordDiff,
+}
+
+/// A shape found with the top-left corner of its (transformed) bounding box at `anchor`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ShapeMatch {
+    pub anchor: Coord,
+    pub transform: Transform,
+}
+
+impl Bounded for WordSearch {
+    fn in_bounds(&self, c: Coord) -> bool {
+        self.get(c).is_some()
+    }
+}
+
+impl WordSearch {
+    pub fn new(inp: &str) -> WordSearch {
+        WordSearch {
+            grid: inp.lines().map(|l| l.chars().collect()).collect(),
+        }
+    }
+
+    pub fn get(&self, c: Coord) -> Option<char> {
+        let row: usize = c.row.try_into().ok()?;
+        let col: usize = c.col.try_into().ok()?;
+        self.grid.get(row).and_then(|v| v.get(col).copied())
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.grid
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| (0..line.len()).map(move |col| Coord::from_xy(col, row)))
+    }
+
+    fn has_word(&self, word: &str, start: Coord, direction: CoordDiff) -> bool {
+        word.chars()
+            .zip(0..)
+            .all(|(ch, idx)| self.get(start + direction * idx) == Some(ch))
+    }
+
+    /// Every occurrence of `word` in any of the eight [`DIRECTIONS`]. A palindrome is found once
+    /// in each direction it reads.
+    pub fn find_word(&self, word: &str) -> Vec<WordMatch> {
+        let Some(first) = word.chars().next() else {
+            return vec![];
+        };
+        self.coords()
+            .filter(|c| self.get(*c) == Some(first))
+            .flat_map(|start| {
+                DIRECTIONS
+                    .into_iter()
+                    .filter(move |d| self.has_word(word, start, *d))
+                    .map(move |direction| WordMatch { start, direction })
+            })
+            .collect()
+    }
+
+    /// Every occurrence of `shape` in any of its distinct orientations.
+    pub fn find_shape(&self, shape: &Shape) -> Vec<ShapeMatch> {
+        let orientations = shape.orientations();
+        self.coords()
+            .flat_map(|anchor| {
+                orientations
+                    .iter()
+                    .filter(move |(_, oriented)| {
+                        oriented
+                            .cells
+                            .iter()
+                            .all(|(offset, ch)| self.get(anchor + *offset) == Some(*ch))
+                    })
+                    .map(move |(transform, _)| ShapeMatch {
+                        anchor,
+                        transform: *transform,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// One of the eight symmetries of a square.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipRows,
+    FlipCols,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipRows,
+        Transform::FlipCols,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Applies the transform to an offset; rotations are clockwise.
+    pub fn apply(&self, d: CoordDiff) -> CoordDiff {
+        let (rows, cols) = match self {
+            Transform::Identity => (d.rows, d.cols),
+            Transform::Rotate90 => (d.cols, -d.rows),
+            Transform::Rotate180 => (-d.rows, -d.cols),
+            Transform::Rotate270 => (-d.cols, d.rows),
+            Transform::FlipRows => (-d.rows, d.cols),
+            Transform::FlipCols => (d.rows, -d.cols),
+            Transform::Transpose => (d.cols, d.rows),
+            Transform::AntiTranspose => (-d.cols, -d.rows),
+        };
+        CoordDiff { rows, cols }
+    }
+}
+
+/// Letters at fixed offsets from each other. Cells that aren't part of the shape match anything.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shape {
+    cells: Vec<(CoordDiff, char)>,
+}
+
+impl Shape {
+    /// Reads a shape drawn as lines of text, where `.` and spaces are cells that match anything.
+    pub fn parse(pattern: &str) -> Shape {
+        let cells = pattern
+            .lines()
+            .zip(0..)
+            .flat_map(|(line, rows)| {
+                line.chars()
+                    .zip(0..)
+                    .filter(|(ch, _)| *ch != '.' && *ch != ' ')
+                    .map(move |(ch, cols)| (CoordDiff { rows, cols }, ch))
+            })
+            .collect();
+        Shape::normalized(cells)
+    }
+
+    /// Shifts the cells so the bounding box starts at (0, 0), and sorts them so equal shapes
+    /// compare equal.
+    fn normalized(mut cells: Vec<(CoordDiff, char)>) -> Shape {
+        let min_row = cells.iter().map(|(d, _)| d.rows).min().unwrap_or(0);
+        let min_col = cells.iter().map(|(d, _)| d.cols).min().unwrap_or(0);
+        for (d, _) in cells.iter_mut() {
+            d.rows -= min_row;
+            d.cols -= min_col;
+        }
+        cells.sort_by_key(|(d, ch)| (d.rows, d.cols, *ch));
+        Shape { cells }
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Shape {
+        Shape::normalized(
+            self.cells
+                .iter()
+                .map(|(d, ch)| (transform.apply(*d), *ch))
+                .collect(),
+        )
+    }
+
+    /// The distinct shapes under all rotations and reflections, each with the first transform
+    /// that produces it.
+    pub fn orientations(&self) -> Vec<(Transform, Shape)> {
+        let mut seen: Vec<(Transform, Shape)> = vec![];
+        for transform in Transform::ALL {
+            let shape = self.transformed(transform);
+            if !seen.iter().any(|(_, it)| *it == shape) {
+                seen.push((transform, shape));
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_word() {
+        let grid = WordSearch::new("..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....");
+        let matches = grid.find_word("XMAS");
+        assert_eq!(matches.len(), 4);
+        assert!(matches.contains(&WordMatch {
+            start: Coord { row: 1, col: 4 },
+            direction: CoordDiff { rows: 0, cols: -1 },
+        }));
+    }
+
+    #[test]
+    fn test_orientations() {
+        let x_mas = Shape::parse("M.S\n.A.\nM.S");
+        assert_eq!(x_mas.orientations().len(), 4);
+        let ell = Shape::parse("A.\nBC");
+        assert_eq!(ell.orientations().len(), 8);
+        assert_eq!(Shape::parse("AA\nAA").orientations().len(), 1);
+    }
+
+    #[test]
+    fn test_find_shape() {
+        let grid = WordSearch::new("S.M\n.A.\nS.M");
+        assert_eq!(
+            grid.find_shape(&Shape::parse("M.S\n.A.\nM.S")),
+            vec![ShapeMatch {
+                anchor: Coord { row: 0, col: 0 },
+                transform: Transform::Rotate180,
+            }]
+        );
+    }
+}